@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+
+/// Heap usage of a single phase of a run.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations
+    pub allocations: u64,
+    /// Total bytes requested by those allocations
+    pub bytes: u64,
+    /// Most bytes live at any one time, over what was live when the phase began
+    pub peak_bytes: u64,
+}
+
+/// Heap usage of parsing an input and of solving one part from it.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub parse: AllocStats,
+    pub solve: AllocStats,
+}
+
+/// Whether the runner was built with the counting allocator.
+#[must_use]
+pub fn enabled() -> bool {
+    cfg!(feature = "alloc-stats")
+}
+
+/// Runs `f`, measuring the heap usage of the current thread while it runs.
+/// Without the `alloc-stats` feature nothing is measured.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    #[cfg(feature = "alloc-stats")]
+    {
+        counting::count_gmp();
+        let start = counting::begin();
+        let value = f();
+        (value, Some(counting::end(start)))
+    }
+    #[cfg(not(feature = "alloc-stats"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::{
+        alloc::{handle_alloc_error, GlobalAlloc, Layout, System},
+        cell::Cell,
+        ffi::c_void,
+        mem,
+        sync::Once,
+    };
+
+    use gmp_mpfr_sys::gmp;
+
+    use super::AllocStats;
+
+    /// The system allocator, counting the allocations of each thread. Counts
+    /// are kept per thread so that days solved in parallel don't see each
+    /// other's allocations.
+    struct CountingAlloc;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    #[derive(Clone, Copy)]
+    struct Counts {
+        allocations: u64,
+        bytes: u64,
+        live: u64,
+        peak: u64,
+    }
+
+    thread_local! {
+        // A const initializer without a destructor keeps the counters usable
+        // from inside the allocator, even while the thread is shutting down.
+        static COUNTS: Cell<Counts> = const {
+            Cell::new(Counts {
+                allocations: 0,
+                bytes: 0,
+                live: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    fn update(f: impl FnOnce(&mut Counts)) {
+        let _ = COUNTS.try_with(|counts| {
+            let mut value = counts.get();
+            f(&mut value);
+            counts.set(value);
+        });
+    }
+
+    fn record_alloc(size: usize) {
+        let size = size as u64;
+        update(|counts| {
+            counts.allocations += 1;
+            counts.bytes += size;
+            counts.live += size;
+            counts.peak = counts.peak.max(counts.live);
+        });
+    }
+
+    fn record_dealloc(size: usize) {
+        // Memory freed by a thread other than the one that allocated it can
+        // take a thread's count below zero, so saturate instead.
+        update(|counts| counts.live = counts.live.saturating_sub(size as u64));
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size());
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    /// Routes GMP's allocations, such as the limbs of a `rug::Integer`, through
+    /// the counting allocator. GMP calls `malloc` directly otherwise, which
+    /// the global allocator never sees.
+    pub(super) fn count_gmp() {
+        static INIT: Once = Once::new();
+        // Blocks GMP allocated before this are freed with `free` either way,
+        // as `System` allocates with `malloc`.
+        INIT.call_once(|| unsafe {
+            gmp::set_memory_functions(Some(gmp_alloc), Some(gmp_realloc), Some(gmp_free));
+        });
+    }
+
+    /// The layout of a GMP block, which holds limbs. GMP never asks for zero
+    /// bytes, but a layout must not be empty.
+    fn gmp_layout(size: usize) -> Layout {
+        Layout::from_size_align(size.max(1), mem::align_of::<gmp::limb_t>())
+            .unwrap_or_else(|_| Layout::new::<gmp::limb_t>())
+    }
+
+    // GMP can't handle an allocation failure, so abort like Rust does.
+    extern "C" fn gmp_alloc(size: usize) -> *mut c_void {
+        let layout = gmp_layout(size);
+        let ptr = unsafe { ALLOCATOR.alloc(layout) };
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        ptr.cast()
+    }
+
+    unsafe extern "C" fn gmp_realloc(
+        ptr: *mut c_void,
+        old_size: usize,
+        new_size: usize,
+    ) -> *mut c_void {
+        let new_ptr = ALLOCATOR.realloc(ptr.cast(), gmp_layout(old_size), new_size.max(1));
+        if new_ptr.is_null() {
+            handle_alloc_error(gmp_layout(new_size));
+        }
+        new_ptr.cast()
+    }
+
+    unsafe extern "C" fn gmp_free(ptr: *mut c_void, size: usize) {
+        ALLOCATOR.dealloc(ptr.cast(), gmp_layout(size));
+    }
+
+    /// Starts measuring a phase, restarting the peak from the bytes live now.
+    pub(super) fn begin() -> (u64, u64, u64) {
+        let mut start = (0, 0, 0);
+        update(|counts| {
+            counts.peak = counts.live;
+            start = (counts.allocations, counts.bytes, counts.live);
+        });
+        start
+    }
+
+    pub(super) fn end((allocations, bytes, live): (u64, u64, u64)) -> AllocStats {
+        let mut stats = AllocStats::default();
+        update(|counts| {
+            stats = AllocStats {
+                allocations: counts.allocations - allocations,
+                bytes: counts.bytes - bytes,
+                peak_bytes: counts.peak.saturating_sub(live),
+            };
+        });
+        stats
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (len, stats) = measure(|| {
+            let small = vec![0u8; 1024];
+            drop(small);
+            vec![0u64; 256].len()
+        });
+        assert_eq!(256, len);
+        match stats {
+            Some(stats) => {
+                assert_eq!(2, stats.allocations);
+                assert_eq!(1024 + 2048, stats.bytes);
+                assert_eq!(2048, stats.peak_bytes);
+            }
+            None => assert!(!enabled()),
+        }
+    }
+
+    #[test]
+    fn test_measure_gmp() {
+        let (bits, stats) = measure(|| (rug::Integer::from(1) << 100_000u32).significant_bits());
+        assert_eq!(100_001, bits);
+        match stats {
+            Some(stats) => assert!(stats.bytes >= 100_000 / 8, "{stats:?}"),
+            None => assert!(!enabled()),
+        }
+    }
+}
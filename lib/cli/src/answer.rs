@@ -0,0 +1,240 @@
+use std::{fmt, str::FromStr};
+
+use rug::Integer;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A puzzle answer. Answers compare equal when their canonical text forms
+/// match, so `Signed(5)`, `Unsigned(5)`, and `Big(5)` are all the same answer.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Big(Integer),
+    Text(String),
+    /// Multi-line answer drawn in ASCII art, e.g. letters rendered on a grid
+    Art(String),
+}
+
+/// The result of comparing two answers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// Identical answers
+    Equal,
+    /// The same answer formatted differently, e.g. `1,024` and `1024`
+    Equivalent,
+    /// Different answers
+    Different,
+}
+
+impl Answer {
+    #[must_use]
+    pub fn art(value: &str) -> Self {
+        Self::Art(value.to_string())
+    }
+
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> Comparison {
+        if self == other {
+            return Comparison::Equal;
+        }
+        let equivalent = match (self.numeric(), other.numeric()) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            (None, None) => normalize_text(&self.to_string()) == normalize_text(&other.to_string()),
+            (_, _) => false,
+        };
+        if equivalent {
+            Comparison::Equivalent
+        } else {
+            Comparison::Different
+        }
+    }
+
+    /// Renders the answer on a single line, escaping newlines in ASCII art.
+    #[must_use]
+    pub fn inline(&self) -> String {
+        self.to_string().replace('\n', "\\n")
+    }
+
+    fn numeric(&self) -> Option<Integer> {
+        match self {
+            Self::Signed(value) => Some(Integer::from(*value)),
+            Self::Unsigned(value) => Some(Integer::from(*value)),
+            Self::Big(value) => Some(value.clone()),
+            Self::Text(value) => {
+                let digits: String = value
+                    .trim()
+                    .trim_start_matches('+')
+                    .chars()
+                    .filter(|c| !matches!(c, ',' | '_' | ' '))
+                    .collect();
+                let unsigned = digits.strip_prefix('-').unwrap_or(&digits);
+                if unsigned.is_empty() || !unsigned.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                Integer::from_str(&digits).ok()
+            }
+            Self::Art(_) => None,
+        }
+    }
+}
+
+fn normalize_text(value: &str) -> String {
+    let lines: Vec<&str> = value.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].join("\n").trim_start().to_string(),
+        (_, _) => String::new(),
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Text(lhs) | Self::Art(lhs), Self::Text(rhs) | Self::Art(rhs)) => lhs == rhs,
+            (_, _) => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signed(value) => write!(f, "{value}"),
+            Self::Unsigned(value) => write!(f, "{value}"),
+            Self::Big(value) => write!(f, "{value}"),
+            Self::Text(value) | Self::Art(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Infers the most specific answer type for `s`: canonical integers become
+    /// integers, anything spanning several lines becomes ASCII art, and
+    /// everything else is text.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim_end_matches(['\r', '\n']);
+        if trimmed.contains('\n') {
+            return Ok(Self::Art(trimmed.to_string()));
+        }
+        if let Ok(value) = trimmed.parse::<u64>() {
+            if value.to_string() == trimmed {
+                return Ok(Self::Unsigned(value));
+            }
+        }
+        if let Ok(value) = trimmed.parse::<i64>() {
+            if value.to_string() == trimmed {
+                return Ok(Self::Signed(value));
+            }
+        }
+        if let Ok(value) = Integer::from_str(trimmed) {
+            if value.to_string() == trimmed {
+                return Ok(Self::Big(value));
+            }
+        }
+        Ok(Self::Text(trimmed.to_string()))
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(value.parse().unwrap_or(Self::Text(value)))
+    }
+}
+
+macro_rules! from_integer {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Self {
+                    Self::$variant(<$target>::from(value))
+                }
+            }
+        )+
+    };
+}
+
+from_integer!(Signed, i64, i8, i16, i32, i64);
+from_integer!(Unsigned, u64, u8, u16, u32, u64);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        u64::try_from(value).map_or_else(|_| Self::Big(Integer::from(value)), Self::Unsigned)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        i64::try_from(value).map_or_else(|_| Self::Big(Integer::from(value)), Self::Signed)
+    }
+}
+
+impl From<Integer> for Answer {
+    fn from(value: Integer) -> Self {
+        Self::Big(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Answer {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(parse("1506483"), Answer::Unsigned(1_506_483)));
+        assert!(matches!(parse("-12"), Answer::Signed(-12)));
+        assert!(matches!(
+            parse("123456789012345678901234567890"),
+            Answer::Big(_)
+        ));
+        assert!(matches!(parse("007"), Answer::Text(_)));
+        assert!(matches!(parse("abc,def"), Answer::Text(_)));
+        assert!(matches!(parse("#..#\n#..#\n"), Answer::Art(_)));
+    }
+
+    #[test]
+    fn test_compare() {
+        let answer = Answer::from(1_506_483_usize);
+        assert_eq!(Comparison::Equal, answer.compare(&parse("1506483")));
+        assert_eq!(
+            Comparison::Equal,
+            answer.compare(&Integer::from(1_506_483).into())
+        );
+        assert_eq!(Comparison::Equivalent, answer.compare(&parse("1,506,483")));
+        assert_eq!(Comparison::Equivalent, answer.compare(&parse(" 1506483")));
+        assert_eq!(Comparison::Different, answer.compare(&parse("1506484")));
+        assert_eq!(Comparison::Different, answer.compare(&parse("abc")));
+
+        let art = Answer::art("#..#\n####\n");
+        assert_eq!(Comparison::Equivalent, art.compare(&parse("#..#  \n####")));
+        assert_eq!(Comparison::Different, art.compare(&parse("#..#\n#..#")));
+    }
+}
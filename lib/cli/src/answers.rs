@@ -0,0 +1,112 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{answer::Answer, part::Part};
+
+const ANSWERS_FILE: &str = "answers.json";
+
+/// Verified answers for a single day, stored alongside its inputs as
+/// `answers.json`. Answers are keyed by the name of the input they were
+/// computed from, e.g. `example_1` or `part_2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, Answer>);
+
+impl Answers {
+    #[must_use]
+    pub fn path(day_dir: &Path) -> PathBuf {
+        day_dir.join(ANSWERS_FILE)
+    }
+
+    /// Reads the answers stored in `day_dir`. A missing answers file is treated
+    /// as having no answers.
+    pub fn read(day_dir: &Path) -> Result<Self> {
+        let path = Self::path(day_dir);
+        match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)
+                .with_context(|| format!("invalid answers file {}", path.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to read answers {}", path.display()))
+            }
+        }
+    }
+
+    pub fn write(&self, day_dir: &Path) -> Result<()> {
+        let path = Self::path(day_dir);
+        let mut data = serde_json::to_string_pretty(self).context("failed to serialize answers")?;
+        data.push('\n');
+        fs::write(&path, data)
+            .with_context(|| format!("failed to write answers {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn get(&self, part: Part, example: bool) -> Option<&Answer> {
+        self.0.get(&key(part, example))
+    }
+
+    pub fn set(&mut self, part: Part, example: bool, answer: Answer) {
+        self.0.insert(key(part, example), answer);
+    }
+
+    /// Stores `answer` as the accepted answer for `part` in `day_dir`,
+    /// replacing any previously recorded value.
+    pub fn record(day_dir: &Path, part: Part, example: bool, answer: Answer) -> Result<()> {
+        if part == Part::Both {
+            bail!("answers are recorded one part at a time");
+        }
+        let mut answers = Self::read(day_dir)?;
+        answers.set(part, example, answer);
+        answers.write(day_dir)
+    }
+}
+
+fn key(part: Part, example: bool) -> String {
+    if example {
+        format!("example_{}", part.value())
+    } else {
+        format!("part_{}", part.value())
+    }
+}
+
+/// The part and input named by an answers key, such as `example_1` or
+/// `part_2`.
+#[must_use]
+pub fn parse_key(key: &str) -> Option<(Part, bool)> {
+    let (input, part) = key.split_once('_')?;
+    let example = match input {
+        "example" => true,
+        "part" => false,
+        _ => return None,
+    };
+    let part = match part {
+        "1" => Part::P1,
+        "2" => Part::P2,
+        _ => return None,
+    };
+    Some((part, example))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        for part in [Part::P1, Part::P2] {
+            for example in [true, false] {
+                assert_eq!(Some((part, example)), parse_key(&key(part, example)));
+            }
+        }
+        assert_eq!(None, parse_key("example_3"));
+        assert_eq!(None, parse_key("test_part_1"));
+    }
+}
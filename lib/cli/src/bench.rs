@@ -0,0 +1,313 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use util::io::InputSource;
+
+use crate::{
+    registry::Registry,
+    runner::{format_duration, print_table, RunSpec},
+};
+
+/// Benchmark settings taken from the command line.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub warmup: u32,
+    pub iterations: u32,
+    pub baseline: Option<PathBuf>,
+    pub save_baseline: Option<PathBuf>,
+    pub regression_threshold: f64,
+}
+
+/// Summary statistics over a set of timing samples, in nanoseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+    pub p95: u64,
+}
+
+impl Stats {
+    #[must_use]
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut nanos: Vec<u64> = samples
+            .iter()
+            .map(|sample| u64::try_from(sample.as_nanos()).unwrap_or(u64::MAX))
+            .collect();
+        nanos.sort_unstable();
+
+        let n = nanos.len();
+        let min = *nanos.first()?;
+        let median = if n % 2 == 0 {
+            (nanos[n / 2 - 1] + nanos[n / 2]) / 2
+        } else {
+            nanos[n / 2]
+        };
+        let total: u128 = nanos.iter().copied().map(u128::from).sum();
+        let mean = u64::try_from(total / n as u128).unwrap_or(u64::MAX);
+        let p95 = nanos[(n * 95).div_ceil(100) - 1];
+
+        Some(Self {
+            min,
+            median,
+            mean,
+            p95,
+        })
+    }
+}
+
+/// Timings for a single day and part, split into parse and solve phases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchEntry {
+    pub day: u16,
+    pub part: u8,
+    /// Solution variant, if one was selected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// A saved set of benchmark results that later runs are compared against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub year: i32,
+    pub example: bool,
+    pub entries: Vec<BenchEntry>,
+}
+
+impl Baseline {
+    pub fn read(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&data).with_context(|| format!("invalid baseline {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let data = serde_json::to_string_pretty(self).context("failed to serialize baseline")?;
+        fs::write(path, data)
+            .with_context(|| format!("failed to write baseline {}", path.display()))
+    }
+
+    /// Fails unless the baseline was saved from the same year and kind of
+    /// input, since timings of other inputs can't be compared.
+    fn check_comparable(&self, year: i32, example: bool) -> Result<()> {
+        let input = |example| if example { "example" } else { "real" };
+        if self.year != year {
+            bail!("the baseline was saved for {}, not {year}", self.year);
+        }
+        if self.example != example {
+            bail!(
+                "the baseline was saved from the {} inputs, not the {} inputs",
+                input(self.example),
+                input(example)
+            );
+        }
+        Ok(())
+    }
+
+    fn entry(&self, current: &BenchEntry) -> Option<&BenchEntry> {
+        self.entries.iter().find(|entry| {
+            (entry.day, entry.part, &entry.variant) == (current.day, current.part, &current.variant)
+        })
+    }
+}
+
+pub fn bench_one(
+    registry: &Registry,
+    base_dir: &Path,
+    spec: RunSpec,
+    options: &BenchOptions,
+) -> Result<BenchEntry> {
+    let RunSpec {
+        day,
+        part,
+        example,
+        variant,
+    } = spec;
+    let solution = registry.get(day)?;
+    let input = InputSource::from(registry.input_path(base_dir, day, part, example)?);
+
+    for _ in 0..options.warmup {
+        solution.parse(&input)?.solve(part, variant)?;
+    }
+
+    let mut parse_samples = Vec::with_capacity(options.iterations as usize);
+    let mut solve_samples = Vec::with_capacity(options.iterations as usize);
+    for _ in 0..options.iterations.max(1) {
+        let start = Instant::now();
+        let parsed = solution.parse(&input)?;
+        parse_samples.push(start.elapsed());
+
+        let start = Instant::now();
+        parsed.solve(part, variant)?;
+        solve_samples.push(start.elapsed());
+    }
+
+    Ok(BenchEntry {
+        day,
+        part: part.value(),
+        variant: variant.map(ToString::to_string),
+        parse: Stats::from_samples(&parse_samples).context("no parse samples")?,
+        solve: Stats::from_samples(&solve_samples).context("no solve samples")?,
+    })
+}
+
+/// Benchmarks every requested day and part, prints the results, and compares
+/// them against a saved baseline when one is given. Regressions larger than
+/// the configured threshold are reported as an error once all days have run.
+pub fn run_bench(
+    registry: &Registry,
+    base_dir: &Path,
+    runs: &[RunSpec],
+    example: bool,
+    options: &BenchOptions,
+) -> Result<()> {
+    let baseline = options
+        .baseline
+        .as_deref()
+        .map(|path| {
+            let baseline = Baseline::read(path)?;
+            baseline
+                .check_comparable(registry.year(), example)
+                .with_context(|| format!("can't compare against {}", path.display()))?;
+            Ok::<_, anyhow::Error>(baseline)
+        })
+        .transpose()?;
+
+    let mut entries = vec![];
+    let mut rows = vec![];
+    let mut failures = 0;
+    let mut regressions = 0;
+
+    for spec in runs {
+        let entry = match bench_one(registry, base_dir, *spec, options) {
+            Ok(entry) => entry,
+            Err(err) => {
+                failures += 1;
+                rows.push([
+                    spec.day.to_string(),
+                    spec.part_label(),
+                    "-".to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    format!("FAIL: {err:#}"),
+                ]);
+                continue;
+            }
+        };
+
+        let previous = baseline
+            .as_ref()
+            .and_then(|baseline| baseline.entry(&entry));
+        for (phase, stats, previous) in [
+            ("parse", entry.parse, previous.map(|x| x.parse)),
+            ("solve", entry.solve, previous.map(|x| x.solve)),
+        ] {
+            let change = previous.map(|previous| percent_change(previous.median, stats.median));
+            let comparison = match change {
+                Some(change) if change > options.regression_threshold => {
+                    regressions += 1;
+                    format!("{change:+.1}% REGRESSION")
+                }
+                Some(change) => format!("{change:+.1}%"),
+                None => String::new(),
+            };
+            rows.push([
+                spec.day.to_string(),
+                spec.part_label(),
+                phase.to_string(),
+                format_nanos(stats.min),
+                format_nanos(stats.median),
+                format_nanos(stats.mean),
+                format_nanos(stats.p95),
+                comparison,
+            ]);
+        }
+        entries.push(entry);
+    }
+
+    print_table(
+        [
+            "day",
+            "part",
+            "phase",
+            "min",
+            "median",
+            "mean",
+            "p95",
+            "vs baseline",
+        ],
+        &rows,
+    );
+
+    if let Some(path) = &options.save_baseline {
+        Baseline {
+            year: registry.year(),
+            example,
+            entries,
+        }
+        .write(path)?;
+        println!();
+        println!("saved baseline to {}", path.display());
+    }
+
+    if failures > 0 {
+        bail!("{failures} benchmarks failed");
+    }
+    if regressions > 0 {
+        bail!(
+            "{regressions} timings regressed by more than {}%",
+            options.regression_threshold
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percent_change(previous: u64, current: u64) -> f64 {
+    if previous == 0 {
+        return 0.0;
+    }
+    (current as f64 - previous as f64) / previous as f64 * 100.0
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format_duration(Duration::from_nanos(nanos))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(1, stats.min);
+        assert_eq!(10, stats.median);
+        assert_eq!(10, stats.mean);
+        assert_eq!(19, stats.p95);
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_check_comparable() {
+        let baseline = Baseline {
+            year: 2024,
+            example: false,
+            entries: vec![],
+        };
+        assert!(baseline.check_comparable(2024, false).is_ok());
+        assert!(baseline.check_comparable(2024, true).is_err());
+        assert!(baseline.check_comparable(2023, false).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    env, fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{
+    output::OutputFormat,
+    part::Part,
+    util::{current_aoc_year, workspace_dir},
+};
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+const DEFAULT_EXAMPLE_FILE: &str = "example_{part}.txt";
+
+/// Settings shared by the runner, downloader, and scaffolding tools. Each
+/// layer overrides the previous one: the user's `~/.config/aoc/aoc.toml`, the
+/// nearest `aoc.toml` in the current directory or its ancestors, then
+/// environment variables. Command line arguments override all of them and are
+/// applied by each tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Data directory. A relative path is resolved against the directory of
+    /// the file that sets it.
+    pub data_dir: Option<PathBuf>,
+    /// AOC challenge year
+    pub year: Option<i32>,
+    /// AOC session cookie
+    pub session: Option<String>,
+    /// Address of the AOC website, e.g. a local stand-in for testing
+    pub base_url: Option<String>,
+    /// Output format
+    pub format: Option<OutputFormat>,
+    /// Name of each example input, where `{part}` is the part number
+    pub example_file: Option<String>,
+    /// Number of days to solve at the same time
+    pub jobs: Option<NonZeroUsize>,
+    /// Solve each day in a child process
+    pub isolate: Option<bool>,
+    /// Memory available to each isolated day, in MiB
+    pub memory_limit: Option<u64>,
+    pub timeouts: Timeouts,
+}
+
+/// Timeouts, in seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timeouts {
+    /// Time allowed for a request to the AOC website
+    pub download: Option<u64>,
+    /// Time allowed for each isolated day
+    pub solve: Option<u64>,
+}
+
+impl Config {
+    /// The configuration of the current process, loaded on first use.
+    pub fn global() -> Result<&'static Self> {
+        static CONFIG: OnceLock<Result<Config>> = OnceLock::new();
+        CONFIG
+            .get_or_init(Self::load)
+            .as_ref()
+            .map_err(|err| anyhow!("{err:#}"))
+    }
+
+    /// Reads and merges every configuration layer.
+    pub fn load() -> Result<Self> {
+        let mut config = Self::default();
+        for path in [user_config_path(), project_config_path()]
+            .into_iter()
+            .flatten()
+        {
+            config = config.merge(Self::read(&path)?);
+        }
+        config.merge(Self::from_env()?).validate()
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&text, dir).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Parses a config file found in `dir`.
+    pub fn parse(text: &str, dir: &Path) -> Result<Self> {
+        let mut config: Self = toml::from_str(text)?;
+        config.data_dir = config.data_dir.map(|data_dir| dir.join(data_dir));
+        Ok(config)
+    }
+
+    /// Settings given by the `AOC_DATA_DIR`, `AOC_YEAR`,
+    /// `AOC_SESSION_COOKIE`, and `AOC_BASE_URL` environment variables.
+    pub fn from_env() -> Result<Self> {
+        let data_dir = env::var_os("AOC_DATA_DIR")
+            .map(|data_dir| {
+                let data_dir = base_dir()?.join(data_dir);
+                fs::canonicalize(&data_dir).with_context(|| {
+                    format!(
+                        "AOC_DATA_DIR {} is not a valid directory",
+                        data_dir.display()
+                    )
+                })
+            })
+            .transpose()?;
+        let year = env::var("AOC_YEAR")
+            .ok()
+            .map(|year| {
+                year.parse()
+                    .with_context(|| format!("AOC_YEAR {year} is not a year"))
+            })
+            .transpose()?;
+        Ok(Self {
+            data_dir,
+            year,
+            session: env::var("AOC_SESSION_COOKIE").ok(),
+            base_url: env::var("AOC_BASE_URL").ok(),
+            ..Self::default()
+        })
+    }
+
+    /// Overrides these settings with any set in `other`.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            data_dir: other.data_dir.or(self.data_dir),
+            year: other.year.or(self.year),
+            session: other.session.or(self.session),
+            base_url: other.base_url.or(self.base_url),
+            format: other.format.or(self.format),
+            example_file: other.example_file.or(self.example_file),
+            jobs: other.jobs.or(self.jobs),
+            isolate: other.isolate.or(self.isolate),
+            memory_limit: other.memory_limit.or(self.memory_limit),
+            timeouts: Timeouts {
+                download: other.timeouts.download.or(self.timeouts.download),
+                solve: other.timeouts.solve.or(self.timeouts.solve),
+            },
+        }
+    }
+
+    fn validate(self) -> Result<Self> {
+        if let Some(year) = self.year.filter(|year| *year < 2015) {
+            bail!("year {year} is before the first AOC in 2015");
+        }
+        if let Some(url) = &self.base_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                bail!("base_url {url} must be an http or https URL");
+            }
+        }
+        if let Some(name) = &self.example_file {
+            if !name.contains("{part}") {
+                bail!("example_file {name} must contain {{part}}");
+            }
+        }
+        Ok(self)
+    }
+
+    /// The configured data directory, or `data` in the workspace root.
+    pub fn data_dir(&self) -> Result<PathBuf> {
+        match &self.data_dir {
+            Some(data_dir) => Ok(data_dir.clone()),
+            None => Ok(base_dir()?.join("data")),
+        }
+    }
+
+    /// The configured year, or the current AOC year.
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year.unwrap_or_else(current_aoc_year)
+    }
+
+    #[must_use]
+    pub fn example_file(&self, part: Part) -> String {
+        self.example_file
+            .as_deref()
+            .unwrap_or(DEFAULT_EXAMPLE_FILE)
+            .replace("{part}", &part.value().to_string())
+    }
+
+    /// The configured number of jobs, or one per core.
+    #[must_use]
+    pub fn jobs(&self) -> NonZeroUsize {
+        self.jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .unwrap_or(NonZeroUsize::MIN)
+    }
+
+    #[must_use]
+    pub fn download_timeout(&self) -> Option<Duration> {
+        self.timeouts.download.map(Duration::from_secs)
+    }
+}
+
+/// The directory a relative data directory from the environment, or the
+/// default one, is resolved against: the workspace root, so that tests run
+/// from a crate's directory find the same data as the runner, or the current
+/// directory outside of a workspace.
+fn base_dir() -> Result<PathBuf> {
+    match workspace_dir() {
+        Some(dir) => Ok(dir),
+        None => env::current_dir().context("invalid current working directory"),
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("aoc").join(CONFIG_FILE)).filter(|path| path.is_file())
+}
+
+fn project_config_path() -> Option<PathBuf> {
+    env::current_dir()
+        .ok()?
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+            data_dir = "inputs"
+            year = 2023
+            format = "json"
+            example_file = "sample_{part}.txt"
+            jobs = 4
+
+            [timeouts]
+            download = 10
+            "#,
+            Path::new("/aoc_rs"),
+        )
+        .unwrap();
+        assert_eq!(Some(PathBuf::from("/aoc_rs/inputs")), config.data_dir);
+        assert_eq!(2023, config.year());
+        assert_eq!(Some(OutputFormat::Json), config.format);
+        assert_eq!("sample_2.txt", config.example_file(Part::P2));
+        assert_eq!(4, config.jobs().get());
+        assert_eq!(Some(Duration::from_secs(10)), config.download_timeout());
+
+        assert!(Config::parse("data_directory = \"data\"", Path::new(".")).is_err());
+        assert!(Config::parse("year = \"2023\"", Path::new(".")).is_err());
+        assert!(Config::parse("jobs = 0", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let user = Config::parse("year = 2022\nsession = \"abc\"", Path::new("/home")).unwrap();
+        let project = Config::parse("year = 2023", Path::new("/aoc_rs")).unwrap();
+        let config = user.merge(project);
+        assert_eq!(Some(2023), config.year);
+        assert_eq!(Some("abc"), config.session.as_deref());
+        assert_eq!("example_1.txt", config.example_file(Part::P1));
+    }
+
+    #[test]
+    fn test_default_data_dir() {
+        // Tests run from the crate's directory, below the workspace root.
+        let data_dir = Config::default().data_dir().unwrap();
+        assert_eq!(workspace_dir().unwrap().join("data"), data_dir);
+        assert!(data_dir.join("2024").is_dir());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = Config::parse("example_file = \"example.txt\"", Path::new(".")).unwrap();
+        assert!(config.validate().is_err());
+        let config = Config::parse("year = 1999", Path::new(".")).unwrap();
+        assert!(config.validate().is_err());
+        let config = Config::parse("base_url = \"localhost:8080\"", Path::new(".")).unwrap();
+        assert!(config.validate().is_err());
+    }
+}
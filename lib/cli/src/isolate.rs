@@ -0,0 +1,334 @@
+use std::{
+    io::Read,
+    path::PathBuf,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    answers::Answers,
+    output::Report,
+    part::Part,
+    runner::{format_duration, Crash, RunOutcome, RunSpec, Verdict},
+    util::example_dir_for_year_and_day,
+};
+
+/// How often a running child is checked for having finished or timed out.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Exit code of a Rust process whose main thread panicked.
+const PANIC_EXIT_CODE: i32 = 101;
+
+/// Solves each day in a child process of the runner, so that a day that
+/// panics, runs out of memory, or never finishes only fails its own runs.
+#[derive(Debug, Clone)]
+pub struct Isolation {
+    pub exe: PathBuf,
+    pub year: i32,
+    pub data_dir: PathBuf,
+    pub timeout: Option<Duration>,
+    /// Address space available to each child, in MiB
+    pub memory_limit: Option<u64>,
+    /// The `--variant` to solve with
+    pub variant: Option<String>,
+    /// Whether to solve with every variant
+    pub all_variants: bool,
+}
+
+impl Isolation {
+    /// Solves the runs of a single day, as grouped by
+    /// [`group_by_day`](crate::runner::group_by_day), in one child process.
+    #[must_use]
+    pub fn run_day(&self, runs: &[RunSpec]) -> Vec<RunOutcome> {
+        let start = Instant::now();
+        let result = self.spawn(runs).and_then(|child| self.wait(child));
+        let elapsed = start.elapsed();
+        let expected = |spec: &RunSpec| {
+            let day_dir = example_dir_for_year_and_day(&self.data_dir, self.year, spec.day);
+            Answers::read(&day_dir)
+                .ok()
+                .and_then(|answers| answers.get(spec.part, spec.example).cloned())
+        };
+        let failed = |crash: Option<Crash>, message: &str| {
+            runs.iter()
+                .map(|spec| RunOutcome {
+                    spec: *spec,
+                    elapsed,
+                    parse_elapsed: None,
+                    result: Err(anyhow!("{message}")),
+                    expected: expected(spec),
+                    crash,
+                    memory: None,
+                })
+                .collect()
+        };
+
+        match result {
+            Ok(Exit::Finished { status, stdout, .. }) if status.success() => {
+                report_outcomes(runs, &stdout)
+                    .unwrap_or_else(|err| failed(None, &format!("{err:#}")))
+            }
+            Ok(Exit::Finished {
+                status,
+                stdout,
+                stderr,
+            }) => match classify(status, &stderr, self.memory_limit.is_some()) {
+                Some((crash, message)) => failed(Some(crash), &message),
+                None => match (report_outcomes(runs, &stdout), signal(status)) {
+                    // The runner exits with an error after reporting runs that
+                    // failed or didn't match their stored answers.
+                    (Ok(outcomes), _) => outcomes,
+                    (Err(_), Some(signal)) => failed(None, &format!("killed by signal {signal}")),
+                    (Err(_), None) => failed(
+                        None,
+                        &format!("worker exited with {status}: {}", stderr.trim()),
+                    ),
+                },
+            },
+            Ok(Exit::TimedOut(timeout)) => failed(
+                Some(Crash::TimedOut),
+                &format!("timed out after {}", format_duration(timeout)),
+            ),
+            Err(err) => failed(None, &format!("{err:#}")),
+        }
+    }
+
+    fn spawn(&self, runs: &[RunSpec]) -> Result<Child> {
+        let [first, ..] = runs else {
+            bail!("no runs to solve");
+        };
+        let part = match runs {
+            [spec] => spec.part,
+            _ if runs.iter().all(|spec| spec.part == first.part) => first.part,
+            _ => Part::Both,
+        };
+        let mut command = Command::new(&self.exe);
+        command
+            .arg("--year")
+            .arg(self.year.to_string())
+            .arg("--data-dir")
+            .arg(&self.data_dir)
+            .args(["--format", "json", "--serial", "--in-process"])
+            .arg(first.day.to_string())
+            .arg(part.to_string());
+        if first.example {
+            command.arg("--example");
+        }
+        if let Some(memory_limit) = self.memory_limit {
+            command.arg("--memory-limit").arg(memory_limit.to_string());
+        }
+        if let Some(variant) = &self.variant {
+            command.arg("--variant").arg(variant);
+        }
+        if self.all_variants {
+            command.arg("--all-variants");
+        }
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.exe.display()))
+    }
+
+    fn wait(&self, mut child: Child) -> Result<Exit> {
+        // Drain the pipes while waiting, since a child that fills a pipe would
+        // otherwise block until it's killed.
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+
+        let deadline = self
+            .timeout
+            .map(|timeout| (timeout, Instant::now() + timeout));
+        let status = loop {
+            if let Some(status) = child.try_wait().context("failed to wait for worker")? {
+                break status;
+            }
+            if let Some((timeout, deadline)) = deadline {
+                if Instant::now() >= deadline {
+                    child.kill().context("failed to stop worker")?;
+                    child.wait().context("failed to wait for worker")?;
+                    return Ok(Exit::TimedOut(timeout));
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        Ok(Exit::Finished {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+}
+
+enum Exit {
+    Finished {
+        status: ExitStatus,
+        stdout: String,
+        stderr: String,
+    },
+    TimedOut(Duration),
+}
+
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            // A partial read still has whatever the child wrote before dying.
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+/// Matches the runs reported by a child with the runs it was asked to solve.
+fn report_outcomes(runs: &[RunSpec], stdout: &str) -> Result<Vec<RunOutcome>> {
+    let report: Report = serde_json::from_str(stdout).context("unexpected output from worker")?;
+    let reports = match report {
+        Report::Runs { runs } => runs,
+        Report::Error { error } => return Err(error.into_error()),
+    };
+    runs.iter()
+        .map(|spec| {
+            let report = reports
+                .iter()
+                .find(|report| {
+                    report.part == spec.part.value() && report.variant.as_deref() == spec.variant
+                })
+                .with_context(|| format!("worker didn't report part {}", spec.part_label()))?;
+            let result = match (&report.answer, &report.error) {
+                (_, Some(error)) => Err(error.clone().into_error()),
+                (Some(answer), None) => Ok(answer.clone()),
+                (None, None) => Err(anyhow!("worker reported no answer")),
+            };
+            Ok(RunOutcome {
+                spec: *spec,
+                elapsed: Duration::from_nanos(report.elapsed_ns),
+                parse_elapsed: report.parse_ns.map(Duration::from_nanos),
+                result,
+                expected: report.expected.clone(),
+                // A worker catches a day that panics, and reports it as such.
+                crash: (report.verdict == Verdict::Panicked).then_some(Crash::Panicked),
+                memory: report.memory,
+            })
+        })
+        .collect()
+}
+
+/// Tells a crashed child apart from one that exited normally, with a message
+/// describing the crash. A child only runs out of memory when it was given a
+/// limit, which makes an allocation beyond it fail rather than summoning the
+/// kernel's OOM killer.
+fn classify(status: ExitStatus, stderr: &str, memory_limited: bool) -> Option<(Crash, String)> {
+    let allocation_failed = ["memory allocation of", "GNU MP: Cannot allocate memory"]
+        .iter()
+        .any(|message| stderr.contains(message));
+    if memory_limited && allocation_failed {
+        return Some((Crash::OutOfMemory, "out of memory".to_string()));
+    }
+    if status.code() == Some(PANIC_EXIT_CODE) {
+        return Some((Crash::Panicked, panic_message(stderr)));
+    }
+    None
+}
+
+/// The location and message of the panic reported in `stderr`, which Rust
+/// prints as `thread 'main' panicked at src/d3.rs:10:5:` followed by the
+/// message.
+fn panic_message(stderr: &str) -> String {
+    let mut lines = stderr.lines();
+    let Some(location) = lines.find_map(|line| line.split_once(" panicked at ")) else {
+        return "panicked".to_string();
+    };
+    let location = location.1.trim_end_matches(':');
+    match lines.next() {
+        Some(message) => format!("panicked at {location}: {message}"),
+        None => format!("panicked at {location}"),
+    }
+}
+
+/// The signal that stopped the process, if any.
+#[cfg(unix)]
+fn signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Caps the address space of the current process at `mib` MiB, so that an
+/// allocation beyond it fails instead of exhausting the machine's memory.
+#[cfg(unix)]
+pub fn set_memory_limit(mib: u64) -> Result<()> {
+    let bytes = mib.saturating_mul(1024 * 1024);
+    let limit = libc::rlimit {
+        rlim_cur: bytes,
+        rlim_max: bytes,
+    };
+    // SAFETY: `limit` is a valid `rlimit` that outlives the call.
+    if unsafe { libc::setrlimit(libc::RLIMIT_AS, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("failed to set the memory limit");
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn set_memory_limit(_mib: u64) -> Result<()> {
+    bail!("--memory-limit is only supported on unix")
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let stderr = "thread 'main' panicked at years/aoc_2024/src/d3.rs:25:14:\nbad grammar: \
+                      expected mul\nnote: run with `RUST_BACKTRACE=1` environment variable\n";
+        assert_eq!(
+            "panicked at years/aoc_2024/src/d3.rs:25:14: bad grammar: expected mul",
+            panic_message(stderr)
+        );
+        assert_eq!("panicked", panic_message(""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_classify() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let panicked = ExitStatus::from_raw(PANIC_EXIT_CODE << 8);
+        assert_eq!(
+            Some(Crash::Panicked),
+            classify(
+                panicked,
+                "thread 'main' panicked at src/d1.rs:1:1:\noops",
+                false
+            )
+            .map(|x| x.0)
+        );
+        let aborted = ExitStatus::from_raw(libc::SIGABRT);
+        let oom = "memory allocation of 1048576 bytes failed\n";
+        assert_eq!(
+            Some(Crash::OutOfMemory),
+            classify(aborted, oom, true).map(|x| x.0)
+        );
+        assert_eq!(None, classify(aborted, oom, false));
+        // Anyone could have sent a SIGKILL, so it isn't taken for the OOM killer.
+        let killed = ExitStatus::from_raw(libc::SIGKILL);
+        assert_eq!(None, classify(killed, "", true));
+        assert_eq!(Some(libc::SIGKILL), signal(killed));
+        assert_eq!(
+            None,
+            classify(ExitStatus::from_raw(1 << 8), "Error: failed", false)
+        );
+    }
+}
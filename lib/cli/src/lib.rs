@@ -5,43 +5,170 @@
     clippy::module_name_repetitions
 )]
 
-use std::path::PathBuf;
+use std::{
+    env,
+    ffi::OsString,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
 
-use anyhow::Result;
-use clap::Parser;
+use ::util::io::InputSource;
+use answer::Answer;
+use answers::Answers;
+use anyhow::{bail, Context, Result};
+use bench::{run_bench, BenchOptions};
+use clap::{Parser, Subcommand};
+use config::Config;
+use isolate::{set_memory_limit, Isolation};
+use output::{print_error, print_runs, OutputFormat};
 use part::Part;
-use util::default_data_dir;
+use registry::Registry;
+use runner::{
+    check_agreement, print_summary, print_verification, run_input, run_many, run_parallel,
+    RunOutcome, RunSpec, Variants,
+};
+use selection::DaySelection;
+use util::current_aoc_year;
+use watch::watch;
 
+pub mod alloc;
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod config;
+pub mod isolate;
+pub mod output;
 pub mod part;
+pub mod registry;
+pub mod runner;
+pub mod selection;
+pub mod solution;
+pub mod testing;
 pub mod util;
+pub mod watch;
 
 /// AOC challenge runner
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
-    /// Data directory
-    #[arg(short, long, default_value=default_data_dir().into_os_string())]
-    pub data_dir: PathBuf,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Data directory, or `data_dir` from aoc.toml, or `data` in the workspace
+    /// root if omitted
+    #[arg(short, long, global = true)]
+    pub data_dir: Option<PathBuf>,
+    /// Output format, or `format` from aoc.toml, or plain if omitted
+    #[arg(short, long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// AOC challenge year, or `year` from aoc.toml, or the current AOC year if
+    /// omitted
+    #[arg(short, long, global = true, value_parser=clap::value_parser!(i32).range(2015..))]
+    pub year: Option<i32>,
     /// Run example
     #[arg(short, long, default_value_t = false)]
     pub example: bool,
-    /// Problem day
-    #[arg(value_parser=clap::value_parser!(u16).range(1..=25))]
-    pub day: u16,
-    /// Problem part
-    #[arg()]
-    pub problem_part: Part,
+    /// Read the input from a file, or from stdin if `-`, instead of the data
+    /// directory
+    #[arg(short, long, conflicts_with_all = ["example", "record", "bench"])]
+    pub input: Option<InputSource>,
+    /// Problem day, range of days (e.g. 1..=10), or all
+    #[arg(required = true)]
+    pub days: Option<DaySelection>,
+    /// Problem part, or every part the day has if omitted or `Both`
+    #[arg(ignore_case = true)]
+    pub problem_part: Option<Part>,
+    /// Record successful answers in each day's answers file
+    #[arg(long, default_value_t = false)]
+    pub record: bool,
+    /// Benchmark the selected days instead of reporting answers
+    #[arg(long, default_value_t = false)]
+    pub bench: bool,
+    /// Untimed benchmark iterations to run first
+    #[arg(long, default_value_t = 3)]
+    pub warmup: u32,
+    /// Timed benchmark iterations
+    #[arg(long, default_value_t = 20)]
+    pub iterations: u32,
+    /// Baseline JSON file to compare benchmark results against
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+    /// Save benchmark results as a baseline JSON file
+    #[arg(long)]
+    pub save_baseline: Option<PathBuf>,
+    /// Median slowdown, in percent, reported as a regression
+    #[arg(long, default_value_t = 10.0)]
+    pub regression_threshold: f64,
+    /// Days to solve at the same time, or `jobs` from aoc.toml, or one per
+    /// core if omitted
+    #[arg(short, long, global = true)]
+    pub jobs: Option<NonZeroUsize>,
+    /// Solve one day at a time, for accurate timings
+    #[arg(long, global = true, default_value_t = false, conflicts_with = "jobs")]
+    pub serial: bool,
+    /// Solve each day in a child process, so that a day that panics or runs
+    /// out of memory only fails its own runs
+    #[arg(long, global = true, default_value_t = false)]
+    pub isolate: bool,
+    /// Seconds each day may run for before it's stopped, or `timeouts.solve`
+    /// from aoc.toml; implies --isolate
+    #[arg(long, global = true)]
+    pub timeout: Option<u64>,
+    /// Memory, in MiB, available to each day, or `memory_limit` from aoc.toml;
+    /// implies --isolate
+    #[arg(long, global = true)]
+    pub memory_limit: Option<u64>,
+    // Global args can't conflict with `--watch`, which subcommands don't have,
+    // so `check_watch` rejects combining the two instead.
+    /// Solve with the named solution variant, for the parts that have one
+    #[arg(long, global = true, conflicts_with = "all_variants")]
+    pub variant: Option<String>,
+    /// Solve with every solution variant and check that their answers agree
+    #[arg(long, global = true, default_value_t = false)]
+    pub all_variants: bool,
+    /// Solve the selected days in this process, used by --isolate to run a day
+    #[arg(long, global = true, default_value_t = false, hide = true)]
+    pub in_process: bool,
+    /// Rebuild and re-run the example and real input whenever the day's
+    /// source, grammar, or data changes
+    #[arg(long, default_value_t = false, conflicts_with_all = ["example", "input", "record", "bench"])]
+    pub watch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Check answers against the answers stored for each day
+    Verify {
+        /// Problem day, range of days (e.g. 1..=10), or all
+        #[arg(default_value = "all")]
+        days: DaySelection,
+        /// Problem part, or every part the day has if omitted or `Both`
+        #[arg(ignore_case = true)]
+        problem_part: Option<Part>,
+    },
 }
 
 pub fn show_result(
     day: u16,
     problem_part: Part,
     example: bool,
-    result: Result<String>,
+    result: Result<Answer>,
 ) -> Result<()> {
     match result {
         Ok(value) => {
             let part_idx = problem_part.value();
+            let value = match value {
+                Answer::Art(art) => format!("\n{art}"),
+                value => value.to_string(),
+            };
             if example {
                 println!("the solution to day {day}, example {part_idx} is: {value}");
             } else {
@@ -55,3 +182,401 @@ pub fn show_result(
         }
     }
 }
+
+/// Fails for options `--watch` doesn't support.
+fn check_watch(format: OutputFormat, variants: Variants) -> Result<()> {
+    if !matches!(variants, Variants::Default) {
+        bail!("--watch can't be combined with --variant or --all-variants");
+    }
+    if format != OutputFormat::Plain {
+        bail!("--watch only supports plain output");
+    }
+    Ok(())
+}
+
+/// Every run for the selected days, part, and variants. Without a part, or
+/// with `Both`, each day runs only the parts it has.
+fn selected_runs(
+    registry: &Registry,
+    days: &[u16],
+    problem_part: Option<Part>,
+    example: bool,
+    variants: Variants,
+) -> Result<Vec<RunSpec>> {
+    let mut runs = vec![];
+    for day in days {
+        let parts = registry.parts(*day, problem_part.unwrap_or(Part::Both))?;
+        runs.extend(RunSpec::all(&[*day], &parts, example));
+    }
+    variants.apply(registry, runs)
+}
+
+fn selected_days(registry: &Registry, days: DaySelection) -> Result<Vec<u16>> {
+    let selected = days.resolve(registry);
+    if selected.is_empty() {
+        bail!("no implemented days match {days}");
+    }
+    Ok(selected)
+}
+
+/// Prints outcomes in the requested format. Returns a failing exit code if
+/// any run failed or produced an answer that doesn't match the stored one.
+fn report_outcomes(
+    format: OutputFormat,
+    year: i32,
+    outcomes: &[RunOutcome],
+    print_plain: fn(&[RunOutcome]),
+) -> Result<ExitCode> {
+    match format {
+        OutputFormat::Plain => print_plain(outcomes),
+        OutputFormat::Json | OutputFormat::Csv => print_runs(format, year, outcomes)?,
+    }
+
+    let failed = outcomes.iter().filter(|outcome| !outcome.passed()).count();
+    if failed > 0 {
+        if format == OutputFormat::Plain {
+            eprintln!("{failed} of {} runs failed", outcomes.len());
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn record_answer(
+    registry: &Registry,
+    data_dir: &Path,
+    spec: RunSpec,
+    answer: &Answer,
+) -> Result<()> {
+    let RunSpec {
+        day, part, example, ..
+    } = spec;
+    Answers::record(
+        &registry.day_dir(data_dir, day),
+        part,
+        example,
+        answer.clone(),
+    )?;
+    let part_idx = part.value();
+    if example {
+        eprintln!("recorded {answer} as the answer to day {day}, example {part_idx}");
+    } else {
+        eprintln!("recorded {answer} as the answer to day {day}, {part_idx}");
+    }
+    Ok(())
+}
+
+/// How a batch of runs is solved.
+#[derive(Debug, Clone)]
+pub struct SolveOptions<'a> {
+    /// Days to solve at the same time
+    pub jobs: NonZeroUsize,
+    pub isolation: Option<Isolation>,
+    pub variants: Variants<'a>,
+}
+
+/// Checks every stored answer for the selected days and parts, covering both
+/// example and real inputs.
+pub fn verify(
+    registry: &Registry,
+    data_dir: &Path,
+    days: DaySelection,
+    problem_part: Option<Part>,
+    format: OutputFormat,
+    options: &SolveOptions,
+) -> Result<ExitCode> {
+    let days = selected_days(registry, days)?;
+    let mut runs = vec![];
+    for example in [true, false] {
+        for spec in selected_runs(registry, &days, problem_part, example, options.variants)? {
+            let answers = Answers::read(&registry.day_dir(data_dir, spec.day))?;
+            if answers.get(spec.part, spec.example).is_some() {
+                runs.push(spec);
+            }
+        }
+    }
+    if runs.is_empty() {
+        bail!("no stored answers for the selected days");
+    }
+
+    let outcomes = solve_runs(registry, data_dir, &runs, options);
+    report_outcomes(format, registry.year(), &outcomes, print_verification)
+}
+
+fn run_with_args(
+    registry: &Registry,
+    config: &Config,
+    args: Args,
+    format: OutputFormat,
+) -> Result<ExitCode> {
+    let data_dir = &match &args.data_dir {
+        Some(data_dir) => data_dir.clone(),
+        None => config.data_dir()?,
+    };
+    let example = args.example;
+    let memory_limit = args.memory_limit.or(config.memory_limit);
+    if let (true, Some(memory_limit)) = (args.in_process, memory_limit) {
+        set_memory_limit(memory_limit)?;
+    }
+    let options = SolveOptions {
+        jobs: if args.serial {
+            NonZeroUsize::MIN
+        } else {
+            args.jobs.unwrap_or_else(|| config.jobs())
+        },
+        isolation: isolation(registry, config, &args, data_dir)?,
+        variants: match (&args.variant, args.all_variants) {
+            (_, true) => Variants::All,
+            (Some(name), false) => Variants::Named(name),
+            (None, false) => Variants::Default,
+        },
+    };
+    let variants = options.variants;
+
+    let days = match (args.command, args.days) {
+        (Some(Command::Verify { days, problem_part }), _) => {
+            return verify(registry, data_dir, days, problem_part, format, &options)
+        }
+        (None, Some(days)) => days,
+        (None, None) => bail!("no days selected"),
+    };
+    let selected_days = selected_days(registry, days)?;
+    let single_part = args.problem_part.filter(|part| *part != Part::Both);
+
+    if args.watch {
+        check_watch(format, variants)?;
+        let day = days.single().context("--watch requires a single day")?;
+        return watch(registry, data_dir, day, args.problem_part);
+    }
+
+    if let Some(input) = args.input {
+        let day = days.single().context("--input requires a single day")?;
+        let input = input.buffered()?;
+        let runs = selected_runs(registry, &[day], args.problem_part, false, variants)?;
+        if let (OutputFormat::Plain, [spec]) = (format, &runs[..]) {
+            let result = registry.solve(*spec, &input);
+            show_result(day, spec.part, false, result)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        let mut outcomes = run_input(registry, &input, &runs);
+        if variants == Variants::All {
+            check_agreement(&mut outcomes);
+        }
+        return report_outcomes(format, registry.year(), &outcomes, print_summary);
+    }
+
+    let runs = selected_runs(
+        registry,
+        &selected_days,
+        args.problem_part,
+        example,
+        variants,
+    )?;
+    if args.bench {
+        if format != OutputFormat::Plain {
+            bail!("--bench only supports plain output, use --save-baseline for JSON");
+        }
+        let options = BenchOptions {
+            warmup: args.warmup,
+            iterations: args.iterations,
+            baseline: args.baseline,
+            save_baseline: args.save_baseline,
+            regression_threshold: args.regression_threshold,
+        };
+        run_bench(registry, data_dir, &runs, example, &options)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let (OutputFormat::Plain, [spec], Some(_), None) =
+        (format, &runs[..], single_part, &options.isolation)
+    {
+        let result = registry.run(data_dir, *spec);
+        let recorded = match (&result, args.record) {
+            (Ok(answer), true) => Some(answer.clone()),
+            _ => None,
+        };
+        show_result(spec.day, spec.part, example, result)?;
+        if let Some(answer) = recorded {
+            record_answer(registry, data_dir, *spec, &answer)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let outcomes = solve_runs(registry, data_dir, &runs, &options);
+    if args.record {
+        for outcome in &outcomes {
+            if let Ok(answer) = &outcome.result {
+                record_answer(registry, data_dir, outcome.spec, answer)?;
+            }
+        }
+    }
+    report_outcomes(format, registry.year(), &outcomes, print_summary)
+}
+
+/// How to isolate each day, if at all. Limits only apply to isolated days, so
+/// setting either one isolates them.
+fn isolation(
+    registry: &Registry,
+    config: &Config,
+    args: &Args,
+    data_dir: &Path,
+) -> Result<Option<Isolation>> {
+    let timeout = args.timeout.or(config.timeouts.solve);
+    let memory_limit = args.memory_limit.or(config.memory_limit);
+    let isolate = args.isolate || config.isolate == Some(true);
+    if args.in_process || !(isolate || timeout.is_some() || memory_limit.is_some()) {
+        return Ok(None);
+    }
+    Ok(Some(Isolation {
+        exe: env::current_exe().context("failed to locate the runner binary")?,
+        year: registry.year(),
+        data_dir: data_dir.to_path_buf(),
+        timeout: timeout.map(Duration::from_secs),
+        memory_limit,
+        variant: args.variant.clone(),
+        all_variants: args.all_variants,
+    }))
+}
+
+/// Solves `runs` in this process or, when isolated, a child process per day.
+fn solve_runs(
+    registry: &Registry,
+    data_dir: &Path,
+    runs: &[RunSpec],
+    options: &SolveOptions,
+) -> Vec<RunOutcome> {
+    let jobs = options.jobs;
+    let mut outcomes = match &options.isolation {
+        Some(isolation) => run_parallel(runs, jobs, |day| isolation.run_day(day)),
+        None => run_parallel(runs, jobs, |day| run_many(registry, data_dir, day)),
+    };
+    if options.variants == Variants::All {
+        check_agreement(&mut outcomes);
+    }
+    outcomes
+}
+
+/// Picks the registry for `year`. Without an explicit or configured year, a
+/// single registry is used as-is and otherwise the current AOC year is
+/// selected.
+fn select_registry(registries: &[Registry], year: Option<i32>) -> Result<&Registry> {
+    let year = match (year, registries) {
+        (None, [registry]) => return Ok(registry),
+        (Some(year), _) => year,
+        (None, _) => current_aoc_year(),
+    };
+    registries
+        .iter()
+        .find(|registry| registry.year() == year)
+        .with_context(|| {
+            let available: Vec<String> = registries
+                .iter()
+                .map(|registry| registry.year().to_string())
+                .collect();
+            format!(
+                "no solutions for {year}, available years: {}",
+                available.join(", ")
+            )
+        })
+}
+
+/// Rewrites a leading year argument, as in `aoc 2024 4 P2`, into `--year`.
+/// Days never exceed 25, so a first argument that parses as a valid AOC year
+/// can't be mistaken for a day selection. The option is moved to the end since
+/// clap stops looking for a subcommand, as in `aoc 2024 verify`, once an
+/// option has been given, but it stays ahead of any `--` separator.
+fn expand_year_argument(mut args: Vec<OsString>) -> Vec<OsString> {
+    let is_year = args
+        .get(1)
+        .and_then(|arg| arg.to_str())
+        .and_then(|arg| arg.parse::<i32>().ok())
+        .is_some_and(|year| year >= 2015);
+    if is_year {
+        let year = args.remove(1);
+        let end = args
+            .iter()
+            .position(|arg| arg == "--")
+            .unwrap_or(args.len());
+        args.splice(end..end, [OsString::from("--year"), year]);
+    }
+    args
+}
+
+/// Parses the command line and runs the requested days from `registry`.
+/// Errors are reported in the requested output format.
+#[must_use]
+pub fn run(registry: &Registry) -> ExitCode {
+    run_years(std::slice::from_ref(registry))
+}
+
+/// Parses the command line and runs the requested days from the registry for
+/// the selected year. Errors are reported in the requested output format.
+#[must_use]
+pub fn run_years(registries: &[Registry]) -> ExitCode {
+    let args = Args::parse_from(expand_year_argument(env::args_os().collect()));
+    let mut format = args.format.unwrap_or_default();
+    Config::global()
+        .and_then(|config| {
+            format = args.format.or(config.format).unwrap_or_default();
+            let registry = select_registry(registries, args.year.or(config.year))?;
+            run_with_args(registry, config, args, format)
+        })
+        .unwrap_or_else(|err| {
+            print_error(format, &err);
+            ExitCode::FAILURE
+        })
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_args() {
+        use clap::CommandFactory;
+
+        Args::command().debug_assert();
+        let parsed = Args::try_parse_from(["aoc", "verify", "--variant", "x"]).unwrap();
+        assert_eq!(Some("x"), parsed.variant.as_deref());
+        assert!(matches!(parsed.command, Some(Command::Verify { .. })));
+    }
+
+    #[test]
+    fn test_select_registry() {
+        let registries = [Registry::new(2023), Registry::new(2024)];
+        let selected = select_registry(&registries, Some(2023)).unwrap();
+        assert_eq!(2023, selected.year());
+        assert!(select_registry(&registries, Some(2015)).is_err());
+        let single = select_registry(&registries[1..], None).unwrap();
+        assert_eq!(2024, single.year());
+    }
+
+    #[test]
+    fn test_expand_year_argument() {
+        assert_eq!(
+            args(&["aoc", "4", "P2", "--year", "2024"]),
+            expand_year_argument(args(&["aoc", "2024", "4", "P2"]))
+        );
+        assert_eq!(
+            args(&["aoc", "verify", "--year", "2024"]),
+            expand_year_argument(args(&["aoc", "2024", "verify"]))
+        );
+        assert_eq!(
+            args(&["aoc", "1", "--year", "2024", "--", "P1"]),
+            expand_year_argument(args(&["aoc", "2024", "1", "--", "P1"]))
+        );
+        assert_eq!(
+            args(&["aoc", "4", "P2"]),
+            expand_year_argument(args(&["aoc", "4", "P2"]))
+        );
+        assert_eq!(
+            args(&["aoc", "--year", "2023", "all"]),
+            expand_year_argument(args(&["aoc", "--year", "2023", "all"]))
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alloc::MemoryStats,
+    answer::Answer,
+    runner::{RunOutcome, Verdict},
+};
+
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[clap(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Plain,
+    Json,
+    Csv,
+}
+
+/// An error and the chain of errors that caused it, outermost first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub message: String,
+    pub chain: Vec<String>,
+}
+
+impl ErrorReport {
+    #[must_use]
+    pub fn new(err: &anyhow::Error) -> Self {
+        Self {
+            message: err.to_string(),
+            chain: err.chain().skip(1).map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Rebuilds the error, e.g. one reported by a child process.
+    #[must_use]
+    pub fn into_error(self) -> anyhow::Error {
+        let mut messages = self.chain.into_iter().rev().chain([self.message]);
+        let mut err = anyhow::anyhow!(messages.next().unwrap_or_default());
+        for message in messages {
+            err = err.context(message);
+        }
+        err
+    }
+}
+
+/// Machine-readable form of a [`RunOutcome`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub year: i32,
+    pub day: u16,
+    pub part: u8,
+    /// Solution variant, if one was selected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub example: bool,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    pub verdict: Verdict,
+    pub elapsed_ns: u64,
+    /// Parse time, on the first of the runs sharing a parse
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse_ns: Option<u64>,
+    pub error: Option<ErrorReport>,
+    /// Heap usage, when built with the `alloc-stats` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryStats>,
+}
+
+impl RunReport {
+    #[must_use]
+    pub fn new(year: i32, outcome: &RunOutcome) -> Self {
+        let (answer, error) = match &outcome.result {
+            Ok(answer) => (Some(answer.clone()), None),
+            Err(err) => (None, Some(ErrorReport::new(err))),
+        };
+        Self {
+            year,
+            day: outcome.spec.day,
+            part: outcome.spec.part.value(),
+            variant: outcome.spec.variant.map(ToString::to_string),
+            example: outcome.spec.example,
+            answer,
+            expected: outcome.expected.clone(),
+            verdict: outcome.verdict(),
+            elapsed_ns: nanos(outcome.elapsed),
+            parse_ns: outcome.parse_elapsed.map(nanos),
+            error,
+            memory: outcome.memory,
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// The JSON document printed by the runner: either the reports of every run
+/// or the error that stopped it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Report {
+    Runs { runs: Vec<RunReport> },
+    Error { error: ErrorReport },
+}
+
+/// Prints run outcomes as a JSON document or as CSV with a header row.
+/// Plain output is handled by the table printers in [`crate::runner`].
+pub fn print_runs(format: OutputFormat, year: i32, outcomes: &[RunOutcome]) -> Result<()> {
+    let runs: Vec<RunReport> = outcomes
+        .iter()
+        .map(|outcome| RunReport::new(year, outcome))
+        .collect();
+    match format {
+        OutputFormat::Plain => {}
+        OutputFormat::Json => {
+            let document = serde_json::to_string_pretty(&Report::Runs { runs })
+                .context("failed to serialize runs")?;
+            println!("{document}");
+        }
+        OutputFormat::Csv => {
+            println!(
+                "year,day,part,variant,example,answer,expected,verdict,parse_ns,elapsed_ns,error"
+            );
+            for run in runs {
+                let error = run
+                    .error
+                    .map(|error| {
+                        std::iter::once(error.message)
+                            .chain(error.chain)
+                            .collect::<Vec<_>>()
+                            .join(": ")
+                    })
+                    .unwrap_or_default();
+                let fields = [
+                    run.year.to_string(),
+                    run.day.to_string(),
+                    run.part.to_string(),
+                    run.variant.unwrap_or_default(),
+                    run.example.to_string(),
+                    run.answer.map(|x| x.to_string()).unwrap_or_default(),
+                    run.expected.map(|x| x.to_string()).unwrap_or_default(),
+                    run.verdict.to_string(),
+                    run.parse_ns.map(|ns| ns.to_string()).unwrap_or_default(),
+                    run.elapsed_ns.to_string(),
+                    error,
+                ];
+                let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                println!("{}", fields.join(","));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reports an error that stopped the runner before any results were produced.
+pub fn print_error(format: OutputFormat, err: &anyhow::Error) {
+    let report = ErrorReport::new(err);
+    match format {
+        OutputFormat::Plain => eprintln!("Error: {err:?}"),
+        OutputFormat::Json => {
+            match serde_json::to_string_pretty(&Report::Error { error: report }) {
+                Ok(document) => println!("{document}"),
+                Err(_) => eprintln!("Error: {err:?}"),
+            }
+        }
+        OutputFormat::Csv => {
+            println!("error,chain");
+            println!(
+                "{},{}",
+                csv_field(&report.message),
+                csv_field(&report.chain.join(": "))
+            );
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!("1506483", csv_field("1506483"));
+        assert_eq!("\"a,b\"", csv_field("a,b"));
+        assert_eq!("\"say \"\"hi\"\"\"", csv_field("say \"hi\""));
+    }
+}
@@ -2,20 +2,26 @@ use std::fmt::Display;
 
 use clap::ValueEnum;
 
-#[derive(ValueEnum, Copy, Clone, Debug, Default)]
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[clap(rename_all = "PascalCase")]
 pub enum Part {
     #[default]
     P1,
     P2,
+    /// Every part the day has, solved from a single parse
+    Both,
 }
 
 impl Part {
+    /// The part number. `Both` isn't a single part and is numbered 0; expand
+    /// it with [`Registry::parts`](crate::registry::Registry::parts) before
+    /// naming inputs or answers.
     #[must_use]
     pub fn value(&self) -> u8 {
         match self {
             Self::P1 => 1,
             Self::P2 => 2,
+            Self::Both => 0,
         }
     }
 }
@@ -25,6 +31,7 @@ impl Display for Part {
         match self {
             Self::P1 => write!(f, "P1"),
             Self::P2 => write!(f, "P2"),
+            Self::Both => write!(f, "Both"),
         }
     }
 }
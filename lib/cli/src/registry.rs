@@ -0,0 +1,178 @@
+use std::{
+    collections::BTreeMap,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use util::io::InputSource;
+
+use crate::{
+    answer::Answer,
+    part::Part,
+    runner::RunSpec,
+    solution::{Solution, DEFAULT_VARIANT},
+    util::{example_dir_for_year_and_day, file_path},
+};
+
+/// Parsed input for a registered day, ready to be solved for either part.
+pub trait Parsed {
+    /// Solves `part` with the named variant, or the day's own solution if
+    /// `variant` is `None`.
+    fn solve(&self, part: Part, variant: Option<&str>) -> Result<Answer>;
+}
+
+/// Type-erased view of a [`Solution`].
+pub trait DynSolution: Send + Sync {
+    fn day(&self) -> u16;
+
+    fn parts(&self) -> &'static [Part];
+
+    /// Names of the solutions to `part`, starting with the [`DEFAULT_VARIANT`].
+    fn variants(&self, part: Part) -> Vec<&'static str>;
+
+    fn parse(&self, input: &InputSource) -> Result<Box<dyn Parsed>>;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn solve(&self, part: Part, variant: Option<&str>) -> Result<Answer> {
+        match (part, variant) {
+            (Part::Both, _) => bail!("parts are solved one at a time"),
+            (Part::P1, None | Some(DEFAULT_VARIANT)) => S::part1(&self.0),
+            (Part::P2, None | Some(DEFAULT_VARIANT)) => S::part2(&self.0),
+            (part, Some(name)) => {
+                let variant = S::variants()
+                    .into_iter()
+                    .find(|variant| variant.part == part && variant.name == name)
+                    .with_context(|| {
+                        format!(
+                            "day {} has no {name} variant of part {}",
+                            S::DAY,
+                            part.value()
+                        )
+                    })?;
+                (variant.solve)(&self.0)
+            }
+        }
+    }
+}
+
+struct Entry<S: Solution>(PhantomData<fn() -> S>);
+
+impl<S> DynSolution for Entry<S>
+where
+    S: Solution + 'static,
+    S::Input: 'static,
+{
+    fn day(&self) -> u16 {
+        S::DAY
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        S::PARTS
+    }
+
+    fn variants(&self, part: Part) -> Vec<&'static str> {
+        let variants = S::variants()
+            .into_iter()
+            .filter(|variant| variant.part == part)
+            .map(|variant| variant.name);
+        [DEFAULT_VARIANT].into_iter().chain(variants).collect()
+    }
+
+    fn parse(&self, input: &InputSource) -> Result<Box<dyn Parsed>> {
+        let parsed = S::parse(input).with_context(|| format!("failed to parse {input}"))?;
+        Ok(Box::new(ParsedInput::<S>(parsed)))
+    }
+}
+
+/// The solutions implemented for a single year, keyed by day.
+pub struct Registry {
+    year: i32,
+    days: BTreeMap<u16, Box<dyn DynSolution>>,
+}
+
+impl Registry {
+    #[must_use]
+    pub fn new(year: i32) -> Self {
+        Self {
+            year,
+            days: BTreeMap::new(),
+        }
+    }
+
+    pub fn register<S>(&mut self) -> &mut Self
+    where
+        S: Solution + 'static,
+        S::Input: 'static,
+    {
+        self.days.insert(S::DAY, Box::new(Entry::<S>(PhantomData)));
+        self
+    }
+
+    #[must_use]
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = u16> + '_ {
+        self.days.keys().copied()
+    }
+
+    pub fn get(&self, day: u16) -> Result<&dyn DynSolution> {
+        self.days
+            .get(&day)
+            .map(AsRef::as_ref)
+            .ok_or(anyhow!("day {day} of {} is not implemented", self.year))
+    }
+
+    /// The parts of `day` selected by `part`. `Both` selects only the parts the
+    /// day has, while a part the day doesn't have is an error.
+    pub fn parts(&self, day: u16, part: Part) -> Result<Vec<Part>> {
+        let parts = self.get(day)?.parts();
+        if part == Part::Both {
+            return Ok(parts.to_vec());
+        }
+        if !parts.contains(&part) {
+            bail!("day {day} of {} has no part {}", self.year, part.value());
+        }
+        Ok(vec![part])
+    }
+
+    #[must_use]
+    pub fn day_dir(&self, base_dir: &Path, day: u16) -> PathBuf {
+        example_dir_for_year_and_day(base_dir, self.year, day)
+    }
+
+    pub fn input_path(
+        &self,
+        base_dir: &Path,
+        day: u16,
+        part: Part,
+        example: bool,
+    ) -> Result<PathBuf> {
+        file_path(&self.day_dir(base_dir, day), part, example)
+    }
+
+    /// The solutions to `part` of `day`, starting with the
+    /// [`DEFAULT_VARIANT`].
+    pub fn variants(&self, day: u16, part: Part) -> Result<Vec<&'static str>> {
+        Ok(self.get(day)?.variants(part))
+    }
+
+    pub fn run(&self, base_dir: &Path, spec: RunSpec) -> Result<Answer> {
+        let path = self.input_path(base_dir, spec.day, spec.part, spec.example)?;
+        self.solve(spec, &InputSource::from(path))
+    }
+
+    /// Solves `spec` for an arbitrary input rather than one from the data
+    /// directory.
+    pub fn solve(&self, spec: RunSpec, input: &InputSource) -> Result<Answer> {
+        self.parts(spec.day, spec.part)?;
+        self.get(spec.day)?
+            .parse(input)?
+            .solve(spec.part, spec.variant)
+    }
+}
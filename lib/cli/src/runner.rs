@@ -0,0 +1,881 @@
+use std::{
+    fmt::Display,
+    fs, mem,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use util::io::InputSource;
+
+use crate::{
+    alloc::{self, AllocStats, MemoryStats},
+    answer::{Answer, Comparison},
+    answers::Answers,
+    output::ErrorReport,
+    part::Part,
+    registry::{Parsed, Registry},
+    solution::DEFAULT_VARIANT,
+};
+
+/// A single day and part to solve, against either the example or the real
+/// input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RunSpec {
+    pub day: u16,
+    pub part: Part,
+    pub example: bool,
+    /// Solution variant, or the day's own solution if `None`
+    pub variant: Option<&'static str>,
+}
+
+impl RunSpec {
+    #[must_use]
+    pub fn new(day: u16, part: Part, example: bool) -> Self {
+        Self {
+            day,
+            part,
+            example,
+            variant: None,
+        }
+    }
+
+    #[must_use]
+    pub fn all(days: &[u16], parts: &[Part], example: bool) -> Vec<Self> {
+        days.iter()
+            .flat_map(|day| {
+                parts
+                    .iter()
+                    .map(move |part| Self::new(*day, *part, example))
+            })
+            .collect()
+    }
+
+    /// The part, followed by the variant solving it if one was selected.
+    #[must_use]
+    pub fn part_label(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("{} ({variant})", self.part),
+            None => self.part.to_string(),
+        }
+    }
+}
+
+/// Which solution variants of each part to run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variants<'a> {
+    /// Each day's own solution
+    Default,
+    /// The named variant, for the parts that have one by that name
+    Named(&'a str),
+    /// Every variant, checking that their answers agree
+    All,
+}
+
+impl Variants<'_> {
+    /// Selects the variants of each run. Parts without the named variant keep
+    /// their own solution, but a name that no part has is an error.
+    pub fn apply(self, registry: &Registry, runs: Vec<RunSpec>) -> Result<Vec<RunSpec>> {
+        let mut selected = vec![];
+        let mut found = false;
+        for spec in runs {
+            match self {
+                Self::Default => selected.push(spec),
+                Self::Named(name) => {
+                    let variant = registry
+                        .variants(spec.day, spec.part)?
+                        .into_iter()
+                        .find(|variant| *variant == name);
+                    found |= variant.is_some();
+                    let variant = variant.filter(|variant| *variant != DEFAULT_VARIANT);
+                    selected.push(RunSpec { variant, ..spec });
+                }
+                Self::All => match registry.variants(spec.day, spec.part)?[..] {
+                    [_] => selected.push(spec),
+                    ref variants => selected.extend(variants.iter().map(|variant| RunSpec {
+                        variant: Some(variant),
+                        ..spec
+                    })),
+                },
+            }
+        }
+        if let (Self::Named(name), false) = (self, found) {
+            bail!("no selected day has a {name} variant");
+        }
+        Ok(selected)
+    }
+}
+
+/// How a run compares with the stored answer for its input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    /// The stored answer, formatted differently
+    Equivalent,
+    Incorrect,
+    Unverified,
+    Failed,
+    TimedOut,
+    Panicked,
+    OutOfMemory,
+}
+
+impl Verdict {
+    #[must_use]
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Correct => "✓",
+            Self::Equivalent => "≈",
+            Self::Incorrect | Self::Failed => "✗",
+            Self::Unverified => "?",
+            Self::TimedOut => "⧗",
+            Self::Panicked | Self::OutOfMemory => "‼",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Equivalent => write!(f, "equivalent"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::Unverified => write!(f, "unverified"),
+            Self::Failed => write!(f, "failed"),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::Panicked => write!(f, "panicked"),
+            Self::OutOfMemory => write!(f, "out of memory"),
+        }
+    }
+}
+
+/// Why a day stopped without reporting its answers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Crash {
+    TimedOut,
+    Panicked,
+    OutOfMemory,
+}
+
+/// The outcome of solving a single day and part.
+#[derive(Debug)]
+pub struct RunOutcome {
+    pub spec: RunSpec,
+    /// Time spent solving, not counting the parse
+    pub elapsed: Duration,
+    /// Time spent parsing the input, reported only by the first of the runs
+    /// sharing that parse
+    pub parse_elapsed: Option<Duration>,
+    pub result: Result<Answer>,
+    pub expected: Option<Answer>,
+    pub crash: Option<Crash>,
+    /// Heap usage, when built with the `alloc-stats` feature
+    pub memory: Option<MemoryStats>,
+}
+
+impl RunOutcome {
+    #[must_use]
+    pub fn verdict(&self) -> Verdict {
+        match self.crash {
+            Some(Crash::TimedOut) => return Verdict::TimedOut,
+            Some(Crash::Panicked) => return Verdict::Panicked,
+            Some(Crash::OutOfMemory) => return Verdict::OutOfMemory,
+            None => {}
+        }
+        match (&self.result, &self.expected) {
+            (Err(_), _) => Verdict::Failed,
+            (Ok(_), None) => Verdict::Unverified,
+            (Ok(actual), Some(expected)) => match actual.compare(expected) {
+                Comparison::Equal => Verdict::Correct,
+                Comparison::Equivalent => Verdict::Equivalent,
+                Comparison::Different => Verdict::Incorrect,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        matches!(
+            self.verdict(),
+            Verdict::Correct | Verdict::Equivalent | Verdict::Unverified
+        )
+    }
+}
+
+/// Solves a single day and part and looks up its stored answer.
+#[must_use]
+pub fn run_one(registry: &Registry, base_dir: &Path, spec: RunSpec) -> RunOutcome {
+    run_shared(registry, base_dir, spec, &mut None)
+}
+
+/// An input parsed for one day, kept so that every part reading the same
+/// file shares a single parse.
+struct SharedParse {
+    day: u16,
+    path: PathBuf,
+    /// The parsed input, or the parse failure every part reports
+    parsed: Result<Box<dyn Parsed>, ErrorReport>,
+    elapsed: Duration,
+    alloc: Option<AllocStats>,
+    /// Whether a run already reported the parse time
+    timed: bool,
+}
+
+impl SharedParse {
+    fn new(registry: &Registry, day: u16, path: PathBuf, input: &InputSource) -> Self {
+        let start = Instant::now();
+        let (parsed, alloc) =
+            alloc::measure(|| registry.get(day).and_then(|solution| solution.parse(input)));
+        Self {
+            day,
+            path,
+            parsed: parsed.map_err(|err| ErrorReport::new(&err)),
+            elapsed: start.elapsed(),
+            alloc,
+            timed: false,
+        }
+    }
+
+    /// Solves `spec`. The first run solved from this parse reports its time.
+    fn solve(&mut self, spec: RunSpec) -> RunOutcome {
+        let start = Instant::now();
+        let (result, alloc) = alloc::measure(|| match &self.parsed {
+            Ok(parsed) => parsed.solve(spec.part, spec.variant),
+            Err(report) => Err(report.clone().into_error()),
+        });
+        let elapsed = start.elapsed();
+        let memory = self
+            .alloc
+            .zip(alloc)
+            .map(|(parse, solve)| MemoryStats { parse, solve });
+        RunOutcome {
+            spec,
+            elapsed,
+            parse_elapsed: (!mem::replace(&mut self.timed, true)).then_some(self.elapsed),
+            result,
+            expected: None,
+            crash: None,
+            memory,
+        }
+    }
+}
+
+/// Solves every requested part of a single day for an input outside the data
+/// directory, parsing it once. There is no stored answer for such an input, so
+/// the outcomes are unverified.
+#[must_use]
+pub fn run_input(registry: &Registry, input: &InputSource, runs: &[RunSpec]) -> Vec<RunOutcome> {
+    let mut shared: Option<SharedParse> = None;
+    runs.iter()
+        .map(|spec| {
+            let mut parse = match shared.take() {
+                Some(parse) if parse.day == spec.day => parse,
+                _ => SharedParse::new(registry, spec.day, PathBuf::new(), input),
+            };
+            let outcome = parse.solve(*spec);
+            shared = Some(parse);
+            outcome
+        })
+        .collect()
+}
+
+/// Solves every requested day and part. Consecutive runs of a day that read
+/// the same input file, such as both parts of the real input, share a single
+/// parse. Failures are recorded in the returned outcomes rather than
+/// aborting the remaining runs.
+#[must_use]
+pub fn run_many(registry: &Registry, base_dir: &Path, runs: &[RunSpec]) -> Vec<RunOutcome> {
+    let mut shared = None;
+    runs.iter()
+        .map(|spec| run_shared(registry, base_dir, *spec, &mut shared))
+        .collect()
+}
+
+/// Splits `runs` into the runs of each day, in order. Each day's runs read
+/// the same kind of input and can share a parse.
+#[must_use]
+pub fn group_by_day(runs: &[RunSpec]) -> Vec<&[RunSpec]> {
+    runs.chunk_by(|lhs, rhs| (lhs.day, lhs.example) == (rhs.day, rhs.example))
+        .collect()
+}
+
+/// Solves every requested day with `run_day`, such as [`run_many`], solving up
+/// to `jobs` days at the same time. Outcomes are returned in the order of
+/// `runs` regardless of which day finishes first. A day that panics fails its
+/// own runs as [`Crash::Panicked`] rather than the whole batch.
+#[must_use]
+pub fn run_parallel<F>(runs: &[RunSpec], jobs: NonZeroUsize, run_day: F) -> Vec<RunOutcome>
+where
+    F: Fn(&[RunSpec]) -> Vec<RunOutcome> + Sync,
+{
+    let days = group_by_day(runs);
+    let workers = jobs.get().min(days.len());
+    let run_day = |day: &[RunSpec]| catch_panic(day, &run_day);
+    if workers <= 1 {
+        return days.into_iter().flat_map(run_day).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let (days, next, run_day, sender) = (&days, &next, &run_day, sender.clone());
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days.get(index) else {
+                    break;
+                };
+                if sender.send((index, run_day(day))).is_err() {
+                    break;
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut outcomes: Vec<(usize, Vec<RunOutcome>)> = receiver.into_iter().collect();
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().flat_map(|(_, day)| day).collect()
+}
+
+/// Solves the runs of a single day with `run_day`, failing all of them if it
+/// panics.
+fn catch_panic<F>(runs: &[RunSpec], run_day: F) -> Vec<RunOutcome>
+where
+    F: Fn(&[RunSpec]) -> Vec<RunOutcome>,
+{
+    let start = Instant::now();
+    // The outcomes of a day that panicked are discarded, so no state it left
+    // behind is observed.
+    let payload = match panic::catch_unwind(AssertUnwindSafe(|| run_day(runs))) {
+        Ok(outcomes) => return outcomes,
+        Err(payload) => payload,
+    };
+    let elapsed = start.elapsed();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+    runs.iter()
+        .map(|spec| RunOutcome {
+            spec: *spec,
+            elapsed,
+            parse_elapsed: None,
+            result: Err(match message {
+                Some(message) => anyhow!("panicked: {message}"),
+                None => anyhow!("panicked"),
+            }),
+            expected: None,
+            crash: Some(Crash::Panicked),
+            memory: None,
+        })
+        .collect()
+}
+
+/// Solves `spec`, reusing the parse in `shared` when it read the same input
+/// and keeping this run's parse there for the next one.
+fn run_shared(
+    registry: &Registry,
+    base_dir: &Path,
+    spec: RunSpec,
+    shared: &mut Option<SharedParse>,
+) -> RunOutcome {
+    let RunSpec {
+        day, part, example, ..
+    } = spec;
+    let path = match registry.input_path(base_dir, day, part, example) {
+        Ok(path) => fs::canonicalize(&path).unwrap_or(path),
+        Err(err) => {
+            return RunOutcome {
+                spec,
+                elapsed: Duration::ZERO,
+                parse_elapsed: None,
+                result: Err(err),
+                expected: None,
+                crash: None,
+                memory: None,
+            }
+        }
+    };
+    let mut parse = match shared.take() {
+        Some(parse) if parse.day == day && parse.path == path => parse,
+        _ => {
+            let input = InputSource::from(path.as_path());
+            SharedParse::new(registry, day, path, &input)
+        }
+    };
+    let mut outcome = parse.solve(spec);
+    *shared = Some(parse);
+
+    match Answers::read(&registry.day_dir(base_dir, day)) {
+        Ok(answers) => outcome.expected = answers.get(part, example).cloned(),
+        Err(err) => outcome.result = outcome.result.and(Err(err)),
+    }
+    outcome
+}
+
+/// Fails every outcome whose answer differs from that of the first variant
+/// solving the same day, part, and input, which is the default variant when
+/// all variants are run.
+pub fn check_agreement(outcomes: &mut [RunOutcome]) {
+    let same_run = |lhs: &RunSpec, rhs: &RunSpec| {
+        (lhs.day, lhs.part, lhs.example) == (rhs.day, rhs.part, rhs.example)
+    };
+    for group in outcomes.chunk_by_mut(|lhs, rhs| same_run(&lhs.spec, &rhs.spec)) {
+        let Some((reference, answer)) = group.iter().find_map(|outcome| {
+            let answer = outcome.result.as_ref().ok()?;
+            Some((
+                outcome.spec.variant.unwrap_or(DEFAULT_VARIANT),
+                answer.clone(),
+            ))
+        }) else {
+            continue;
+        };
+        for outcome in group.iter_mut() {
+            let disagrees = matches!(
+                &outcome.result,
+                Ok(actual) if actual.compare(&answer) == Comparison::Different
+            );
+            if disagrees {
+                outcome.result = Err(anyhow!(
+                    "disagrees with the {reference} variant, which answered {}",
+                    answer.inline()
+                ));
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos} ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.2} µs", duration.as_secs_f64() * 1e6)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2} ms", duration.as_secs_f64() * 1e3)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+/// Prints one row per outcome followed by a count of each verdict.
+pub fn print_summary(outcomes: &[RunOutcome]) {
+    let rows: Vec<[String; 6]> = outcomes
+        .iter()
+        .map(|outcome| {
+            let answer = match &outcome.result {
+                Ok(answer) => answer.inline(),
+                Err(err) => format!("{err:#}"),
+            };
+            let verdict = outcome.verdict();
+            let status = match (verdict, &outcome.expected) {
+                (Verdict::Incorrect | Verdict::Equivalent, Some(expected)) => {
+                    format!("{} expected {}", verdict.symbol(), expected.inline())
+                }
+                (Verdict::TimedOut | Verdict::Panicked | Verdict::OutOfMemory, _) => {
+                    format!("{} {verdict}", verdict.symbol())
+                }
+                _ => verdict.symbol().to_string(),
+            };
+            [
+                outcome.spec.day.to_string(),
+                outcome.spec.part_label(),
+                answer,
+                outcome
+                    .parse_elapsed
+                    .map(format_duration)
+                    .unwrap_or_default(),
+                format_duration(outcome.elapsed),
+                status,
+            ]
+        })
+        .collect();
+    print_table(["day", "part", "answer", "parse", "time", "status"], &rows);
+    print_counts(outcomes);
+    print_memory(outcomes);
+}
+
+/// Prints the heap usage of each phase of every outcome that measured it.
+fn print_memory(outcomes: &[RunOutcome]) {
+    let mut rows = vec![];
+    for outcome in outcomes {
+        let Some(memory) = outcome.memory else {
+            continue;
+        };
+        for (phase, stats) in [("parse", memory.parse), ("solve", memory.solve)] {
+            rows.push([
+                outcome.spec.day.to_string(),
+                outcome.spec.part_label(),
+                phase.to_string(),
+                stats.allocations.to_string(),
+                format_bytes(stats.bytes),
+                format_bytes(stats.peak_bytes),
+            ]);
+        }
+    }
+    if rows.is_empty() {
+        return;
+    }
+    println!();
+    print_table(
+        ["day", "part", "phase", "allocations", "allocated", "peak"],
+        &rows,
+    );
+}
+
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.2} {}", UNITS[unit])
+}
+
+/// Prints the expected and actual answer of each outcome.
+pub fn print_verification(outcomes: &[RunOutcome]) {
+    let rows: Vec<[String; 6]> = outcomes
+        .iter()
+        .map(|outcome| {
+            let actual = match &outcome.result {
+                Ok(answer) => answer.inline(),
+                Err(err) => format!("error: {err:#}"),
+            };
+            let input = if outcome.spec.example {
+                "example"
+            } else {
+                "input"
+            };
+            [
+                outcome.spec.day.to_string(),
+                outcome.spec.part_label(),
+                input.to_string(),
+                outcome
+                    .expected
+                    .as_ref()
+                    .map(Answer::inline)
+                    .unwrap_or_default(),
+                actual,
+                outcome.verdict().symbol().to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        ["day", "part", "input", "expected", "actual", "result"],
+        &rows,
+    );
+    print_counts(outcomes);
+}
+
+fn print_counts(outcomes: &[RunOutcome]) {
+    let count = |verdict: Verdict| {
+        outcomes
+            .iter()
+            .filter(|outcome| outcome.verdict() == verdict)
+            .count()
+    };
+    println!();
+    let mut counts = format!(
+        "{} correct, {} equivalent, {} unverified, {} incorrect, {} failed",
+        count(Verdict::Correct),
+        count(Verdict::Equivalent),
+        count(Verdict::Unverified),
+        count(Verdict::Incorrect),
+        count(Verdict::Failed)
+    );
+    // Crashes are rare, so only mention them when they happen.
+    for verdict in [Verdict::TimedOut, Verdict::Panicked, Verdict::OutOfMemory] {
+        if count(verdict) > 0 {
+            counts.push_str(&format!(", {} {verdict}", count(verdict)));
+        }
+    }
+    println!("{counts}");
+}
+
+pub(crate) fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) {
+    let mut widths = header.map(|column| column.chars().count());
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, value.chars().count());
+        }
+    }
+
+    let print_row = |row: &[&str]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+    };
+
+    print_row(&header);
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    println!("{}", rule.join("-|-"));
+    for row in rows {
+        print_row(&row.each_ref().map(String::as_str));
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        output::RunReport,
+        solution::{Solution, Variant},
+    };
+
+    static PARSES: AtomicUsize = AtomicUsize::new(0);
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u16 = 1;
+        type Input = Vec<u64>;
+
+        fn parse(input: &InputSource) -> Result<Self::Input> {
+            Ok(input
+                .read_lines()?
+                .iter()
+                .map(|line| line.parse())
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Ok(Answer::Unsigned(input.iter().sum()))
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            Ok(Answer::Unsigned(input.iter().product()))
+        }
+
+        fn variants() -> Vec<Variant<Self::Input>> {
+            vec![
+                Variant::new("reversed", Part::P1, |input| {
+                    Ok(Answer::Unsigned(input.iter().rev().sum()))
+                }),
+                Variant::new("off_by_one", Part::P1, |input| {
+                    Ok(Answer::Unsigned(input.iter().sum::<u64>() + 1))
+                }),
+            ]
+        }
+    }
+
+    /// [`Sum`] as day 2, counting how often its input is parsed
+    struct Counted;
+
+    impl Solution for Counted {
+        const DAY: u16 = 2;
+        type Input = Vec<u64>;
+
+        fn parse(input: &InputSource) -> Result<Self::Input> {
+            PARSES.fetch_add(1, Ordering::SeqCst);
+            Sum::parse(input)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Sum::part1(input)
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            Sum::part2(input)
+        }
+    }
+
+    struct FinalDay;
+
+    impl Solution for FinalDay {
+        const DAY: u16 = 25;
+        const PARTS: &'static [Part] = &[Part::P1];
+        type Input = ();
+
+        fn parse(_input: &InputSource) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1((): &Self::Input) -> Result<Answer> {
+            Ok(Answer::Text("done".to_string()))
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new(2024);
+        registry
+            .register::<Sum>()
+            .register::<Counted>()
+            .register::<FinalDay>();
+        registry
+    }
+
+    #[test]
+    fn test_both_parts_share_a_parse() {
+        let registry = registry();
+        let runs = RunSpec::all(&[2], &registry.parts(2, Part::Both).unwrap(), false);
+        let outcomes = run_input(&registry, &InputSource::text("2\n3\n4\n"), &runs);
+        assert_eq!(1, PARSES.load(Ordering::SeqCst));
+
+        let answers: Vec<(Part, String)> = outcomes
+            .iter()
+            .map(|outcome| (outcome.spec.part, outcome.result.as_ref().unwrap().inline()))
+            .collect();
+        assert_eq!(
+            vec![(Part::P1, "9".to_string()), (Part::P2, "24".to_string())],
+            answers
+        );
+        // The parse is timed once, rather than as part of both answers.
+        assert!(outcomes[0].parse_elapsed.is_some());
+        assert!(outcomes[1].parse_elapsed.is_none());
+    }
+
+    #[test]
+    fn test_parse_error_chain() {
+        let registry = registry();
+        let runs = RunSpec::all(&[1], &[Part::P1, Part::P2], false);
+        let outcomes = run_many(&registry, Path::new("/nonexistent"), &runs);
+
+        for outcome in &outcomes {
+            let report = serde_json::to_value(RunReport::new(2024, outcome)).unwrap();
+            let chain = report["error"]["chain"].as_array().unwrap();
+            assert!(!chain.is_empty(), "{report}");
+        }
+    }
+
+    #[test]
+    fn test_run_parallel_order() {
+        let registry = registry();
+        let dir = tempdir().unwrap();
+        let base_dir = dir.path();
+        for day in [1, 25] {
+            let day_dir = registry.day_dir(base_dir, day);
+            fs::create_dir_all(&day_dir).unwrap();
+            fs::write(day_dir.join("input.txt"), "3\n4\n").unwrap();
+        }
+
+        let mut runs = RunSpec::all(&[25], &[Part::P1], false);
+        runs.extend(RunSpec::all(&[1], &[Part::P1, Part::P2], false));
+        let jobs = NonZeroUsize::new(4).unwrap();
+        let answers = |outcomes: Vec<RunOutcome>| -> Vec<(u16, Part, String)> {
+            outcomes
+                .into_iter()
+                .map(|outcome| {
+                    let RunSpec { day, part, .. } = outcome.spec;
+                    (day, part, outcome.result.unwrap().inline())
+                })
+                .collect()
+        };
+        let parallel = answers(run_parallel(&runs, jobs, |day| {
+            run_many(&registry, base_dir, day)
+        }));
+        let serial = answers(run_many(&registry, base_dir, &runs));
+
+        assert_eq!(
+            vec![
+                (25, Part::P1, "done".to_string()),
+                (1, Part::P1, "7".to_string()),
+                (1, Part::P2, "12".to_string()),
+            ],
+            parallel
+        );
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_run_parallel_panic() {
+        let registry = registry();
+        let mut runs = RunSpec::all(&[25], &[Part::P1], false);
+        runs.extend(RunSpec::all(&[1], &[Part::P1, Part::P2], false));
+        let input = InputSource::text("2\n3\n");
+        for jobs in [1, 2] {
+            let outcomes = run_parallel(&runs, NonZeroUsize::new(jobs).unwrap(), |day| {
+                assert_ne!(25, day[0].day, "no answer today");
+                run_input(&registry, &input, day)
+            });
+            let verdicts: Vec<_> = outcomes.iter().map(RunOutcome::verdict).collect();
+            assert_eq!(
+                vec![Verdict::Panicked, Verdict::Unverified, Verdict::Unverified],
+                verdicts
+            );
+            let err = outcomes[0].result.as_ref().unwrap_err();
+            assert!(err.to_string().contains("no answer today"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_variants() {
+        let registry = registry();
+        let runs = RunSpec::all(&[1], &[Part::P1, Part::P2], false);
+        let named = Variants::Named("reversed")
+            .apply(&registry, runs.clone())
+            .unwrap();
+        let labels: Vec<String> = named.iter().map(RunSpec::part_label).collect();
+        assert_eq!(vec!["P1 (reversed)", "P2"], labels);
+        assert!(Variants::Named("missing")
+            .apply(&registry, runs.clone())
+            .is_err());
+
+        let all = Variants::All.apply(&registry, runs).unwrap();
+        let mut outcomes = run_input(&registry, &InputSource::text("2\n3\n"), &all);
+        check_agreement(&mut outcomes);
+        let results: Vec<(String, Result<String, String>)> = outcomes
+            .iter()
+            .map(|outcome| {
+                let result = match &outcome.result {
+                    Ok(answer) => Ok(answer.inline()),
+                    Err(err) => Err(err.to_string()),
+                };
+                (outcome.spec.part_label(), result)
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("P1 (default)".to_string(), Ok("5".to_string())),
+                ("P1 (reversed)".to_string(), Ok("5".to_string())),
+                (
+                    "P1 (off_by_one)".to_string(),
+                    Err("disagrees with the default variant, which answered 5".to_string())
+                ),
+                ("P2".to_string(), Ok("6".to_string())),
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.50 KiB", format_bytes(1536));
+        assert_eq!("2.00 GiB", format_bytes(2 << 30));
+    }
+
+    #[test]
+    fn test_final_day_parts() {
+        let registry = registry();
+        assert_eq!(vec![Part::P1], registry.parts(25, Part::Both).unwrap());
+        assert_eq!(vec![Part::P2], registry.parts(1, Part::P2).unwrap());
+        assert!(registry.parts(25, Part::P2).is_err());
+        assert!(registry
+            .solve(RunSpec::new(25, Part::P2, false), &InputSource::text(""))
+            .is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::registry::Registry;
+
+const DAYS: RangeInclusive<u16> = 1..=25;
+
+/// The days requested on the command line: a single day, a range of days
+/// (`1..=10` or `1..10`), or `all`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Range { first: u16, last: u16 },
+}
+
+impl DaySelection {
+    #[must_use]
+    pub fn single(&self) -> Option<u16> {
+        match self {
+            Self::Range { first, last } if first == last => Some(*first),
+            _ => None,
+        }
+    }
+
+    /// Every day covered by the selection, implemented or not.
+    #[must_use]
+    pub fn days(&self) -> RangeInclusive<u16> {
+        match self {
+            Self::All => DAYS,
+            Self::Range { first, last } => *first..=*last,
+        }
+    }
+
+    /// Resolves the selection against the days implemented in `registry`. A
+    /// single day is always returned as-is so that running an unimplemented
+    /// day reports an error rather than doing nothing.
+    #[must_use]
+    pub fn resolve(&self, registry: &Registry) -> Vec<u16> {
+        match (self, self.single()) {
+            (_, Some(day)) => vec![day],
+            (_, None) => registry
+                .days()
+                .filter(|day| self.days().contains(day))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        fn parse_day(s: &str) -> Result<u16> {
+            let day: u16 = s.trim().parse().context(format!("invalid day {s}"))?;
+            if !DAYS.contains(&day) {
+                bail!("day {day} is not in 1..=25");
+            }
+            Ok(day)
+        }
+
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+
+        let (first, last) = if let Some((first, last)) = s.split_once("..=") {
+            (parse_day(first)?, parse_day(last)?)
+        } else if let Some((first, end)) = s.split_once("..") {
+            // The end of a half-open range is one past the last day, so `..26`
+            // selects up to day 25.
+            let end: u16 = end.trim().parse().context(format!("invalid day {end}"))?;
+            let last = end.checked_sub(1).ok_or(anyhow!("empty day range {s}"))?;
+            if last > *DAYS.end() {
+                bail!("day range {s} ends after day {}", DAYS.end());
+            }
+            (parse_day(first)?, last)
+        } else {
+            let day = parse_day(s)?;
+            (day, day)
+        };
+
+        if first > last {
+            bail!("empty day range {s}");
+        }
+        Ok(Self::Range { first, last })
+    }
+}
+
+impl Display for DaySelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Range { first, last } if first == last => write!(f, "{first}"),
+            Self::Range { first, last } => write!(f, "{first}..={last}"),
+        }
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(DaySelection::All, "all".parse().unwrap());
+        assert_eq!(
+            DaySelection::Range { first: 4, last: 4 },
+            "4".parse().unwrap()
+        );
+        assert_eq!(
+            DaySelection::Range { first: 1, last: 10 },
+            "1..=10".parse().unwrap()
+        );
+        assert_eq!(
+            DaySelection::Range { first: 1, last: 9 },
+            "1..10".parse().unwrap()
+        );
+        assert_eq!(
+            DaySelection::Range { first: 1, last: 25 },
+            "1..26".parse().unwrap()
+        );
+        assert!("1..27".parse::<DaySelection>().is_err());
+        assert!("0".parse::<DaySelection>().is_err());
+        assert!("26".parse::<DaySelection>().is_err());
+        assert!("5..=3".parse::<DaySelection>().is_err());
+        assert!("3..3".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let registry = Registry::new(2024);
+        assert!(DaySelection::All.resolve(&registry).is_empty());
+        assert_eq!(
+            vec![7],
+            "7".parse::<DaySelection>().unwrap().resolve(&registry)
+        );
+        assert_eq!(1..=25, DaySelection::All.days());
+        assert_eq!(3..=5, "3..=5".parse::<DaySelection>().unwrap().days());
+    }
+}
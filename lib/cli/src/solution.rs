@@ -0,0 +1,69 @@
+use anyhow::{bail, Result};
+use util::io::InputSource;
+
+use crate::{answer::Answer, part::Part};
+
+/// A single day's puzzle. Input is parsed once and shared by both parts.
+pub trait Solution {
+    /// Problem day
+    const DAY: u16;
+
+    /// The parts this day has. The final day of a year only has a first part,
+    /// so it overrides this with `&[Part::P1]` and leaves `part2` unsolved.
+    const PARTS: &'static [Part] = &[Part::P1, Part::P2];
+
+    /// Parsed puzzle input
+    type Input;
+
+    fn parse(input: &InputSource) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        bail!("day {} has no part 2", Self::DAY)
+    }
+
+    /// Alternative solutions to either part, such as a naive solution kept
+    /// next to an optimized one. `part1` and `part2` are the
+    /// [`DEFAULT_VARIANT`].
+    #[must_use]
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![]
+    }
+}
+
+/// Name of the solution given by [`Solution::part1`] and [`Solution::part2`].
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// A named alternative solution to one part of a day, selected with
+/// `--variant`.
+pub struct Variant<I> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: fn(&I) -> Result<Answer>,
+}
+
+impl<I> Variant<I> {
+    #[must_use]
+    pub fn new(name: &'static str, part: Part, solve: fn(&I) -> Result<Answer>) -> Self {
+        Self { name, part, solve }
+    }
+}
+
+/// Declares the public day modules of a year crate and a public `registry()`
+/// function that registers the `Day` solution of each module.
+#[macro_export]
+macro_rules! days {
+    ($year:expr; $($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Solutions for every implemented day of the year
+        #[must_use]
+        pub fn registry() -> $crate::registry::Registry {
+            #[allow(unused_mut)]
+            let mut registry = $crate::registry::Registry::new($year);
+            $(registry.register::<$module::Day>();)*
+            registry
+        }
+    };
+}
@@ -0,0 +1,119 @@
+use std::io::{self, Write};
+
+use anyhow::{bail, Context, Result};
+use util::io::InputSource;
+
+use crate::{
+    answer::{Answer, Comparison},
+    answers::{self, Answers},
+    registry::Registry,
+    util::default_data_dir,
+};
+
+/// Result of a test generated by [`day_tests!`](crate::day_tests).
+pub type TestResult = Result<()>;
+
+/// Solves the input of `day` named as in its answers file, e.g. `example_1`
+/// or `part_2`, with every variant of the part. Each answer must match
+/// `expected` or, if that's omitted, the stored answer, and an expected answer
+/// that contradicts the stored one is an error too.
+///
+/// Examples are checked in with the solutions, but AOC asks that personal
+/// inputs not be shared, so a missing `input.txt` skips the check instead of
+/// failing it.
+pub fn check_answer(
+    registry: &Registry,
+    day: u16,
+    name: &str,
+    expected: Option<Answer>,
+) -> TestResult {
+    let (part, example) = answers::parse_key(name)
+        .with_context(|| format!("{name} doesn't name an input, e.g. example_1 or part_2"))?;
+    let data_dir = default_data_dir()?;
+    let path = registry.input_path(&data_dir, day, part, example)?;
+    if !example && !path.exists() {
+        skip(&format!(
+            "day {day} {name}: no personal input at {}, download it with aoc_downloader",
+            path.display()
+        ));
+        return Ok(());
+    }
+    let day_dir = registry.day_dir(&data_dir, day);
+    let stored = Answers::read(&day_dir)?.get(part, example).cloned();
+    let expected = match (expected, stored) {
+        (Some(expected), Some(stored)) if expected.compare(&stored) == Comparison::Different => {
+            bail!(
+                "expected {} for {name}, but {} stores {}",
+                expected.inline(),
+                Answers::path(&day_dir).display(),
+                stored.inline()
+            )
+        }
+        (Some(expected), _) | (None, Some(expected)) => expected,
+        (None, None) => bail!("no expected or stored answer for {name} of day {day}"),
+    };
+
+    let input = InputSource::from(path);
+    let parsed = registry.get(day)?.parse(&input)?;
+    for variant in registry.variants(day, part)? {
+        let answer = parsed
+            .solve(part, Some(variant))
+            .with_context(|| format!("the {variant} variant failed to solve {name}"))?;
+        if answer.compare(&expected) != Comparison::Equal {
+            bail!(
+                "the {variant} variant answered {} for {name}, expected {}",
+                answer.inline(),
+                expected.inline()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reports a skipped check. The test harness captures `println!` and
+/// `eprintln!` from passing tests, so this writes to stderr directly to keep
+/// the reason visible.
+fn skip(reason: &str) {
+    let _ = writeln!(io::stderr(), "skipped {reason}");
+}
+
+/// Declares a test for each listed input of a day, named as in the day's
+/// answers file. Each test solves its input with every variant through the
+/// crate's `registry()` and checks the answer against the one given, or the
+/// stored one when it's omitted:
+///
+/// ```ignore
+/// cli::day_tests! {
+///     Day;
+///     example_1 = 11,
+///     example_2 = 31,
+///     part_1,
+///     #[ignore = "slow"]
+///     part_2,
+/// }
+/// ```
+// `crate::registry()` deliberately refers to the year crate using the macro.
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! day_tests {
+    ($day:ty; $($(#[$meta:meta])* $name:ident $(= $expected:expr)?),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[test]
+            fn $name() -> $crate::testing::TestResult {
+                $crate::testing::check_answer(
+                    &crate::registry(),
+                    <$day as $crate::solution::Solution>::DAY,
+                    stringify!($name),
+                    $crate::day_tests!(@expected $($expected)?),
+                )
+            }
+        )*
+    };
+    (@expected) => {
+        None
+    };
+    (@expected $expected:expr) => {
+        Some($crate::answer::Answer::from($expected))
+    };
+}
@@ -1,37 +1,31 @@
 use std::{
-    env,
-    ffi::OsString,
-    fs::canonicalize,
+    env, fs,
     path::{Path, PathBuf},
-    str::FromStr,
 };
 
+use anyhow::Result;
 use chrono::{Datelike, Utc};
 
-use crate::part::Part;
+use crate::{config::Config, part::Part};
 
-#[must_use]
-pub fn default_data_dir() -> PathBuf {
-    if let Ok(aoc_dir) = env::var("AOC_DATA_DIR") {
-        canonicalize(PathBuf::from(aoc_dir)).expect("failed to canonicalize AOC_DATA_DIR")
-    } else {
-        let mut base = env::current_dir().expect("invalid current working directory");
-        base.push("data");
-        base
-    }
+/// Name of a day's real input, which both parts read.
+pub const INPUT_FILE: &str = "input.txt";
+
+/// The data directory from `AOC_DATA_DIR` or `data_dir` in aoc.toml, or
+/// `data` in the workspace root. See [`Config::data_dir`].
+pub fn default_data_dir() -> Result<PathBuf> {
+    Config::global()?.data_dir()
 }
 
+/// The most recent AOC year: this year in December, last year otherwise.
 #[must_use]
-pub fn current_year() -> OsString {
+pub fn current_aoc_year() -> i32 {
     let current_date = Utc::now();
-    let year = current_date.year();
-    let month = current_date.month();
-    let year_str = if month == 12 {
-        year.to_string()
+    if current_date.month() == 12 {
+        current_date.year()
     } else {
-        (year - 1).to_string()
-    };
-    OsString::from_str(&year_str).expect("invalid aoc year")
+        current_date.year() - 1
+    }
 }
 
 #[must_use]
@@ -39,11 +33,25 @@ pub fn example_dir_for_year_and_day(base_dir: &Path, year: i32, day: u16) -> Pat
     base_dir.join(year.to_string()).join(format!("{day:02}"))
 }
 
-#[must_use]
-pub fn file_path(example_dir: &Path, part: Part, example: bool) -> PathBuf {
+/// The input `part` reads: its own example, as named in aoc.toml, or the
+/// day's single real input.
+pub fn file_path(example_dir: &Path, part: Part, example: bool) -> Result<PathBuf> {
     if example {
-        example_dir.join(format!("example_{}.txt", part.value()))
+        Ok(example_dir.join(Config::global()?.example_file(part)))
     } else {
-        example_dir.join(format!("part_{}.txt", part.value()))
+        Ok(example_dir.join(INPUT_FILE))
     }
 }
+
+/// Finds the root of the cargo workspace containing the current directory.
+#[must_use]
+pub fn workspace_dir() -> Option<PathBuf> {
+    let current_dir = env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .find(|dir| {
+            fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.contains("[workspace]"))
+        })
+        .map(Path::to_path_buf)
+}
@@ -0,0 +1,329 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use notify::{
+    event::{AccessKind, AccessMode, ModifyKind},
+    Event, EventKind, RecursiveMode, Watcher,
+};
+
+use crate::{
+    answer::Answer,
+    output::{Report, RunReport},
+    part::Part,
+    registry::Registry,
+    runner::{format_duration, print_table},
+    util::workspace_dir,
+};
+
+/// How long to wait for further changes before re-running, since editors and
+/// downloads often touch a file several times in quick succession.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The files that affect a single day's answers: its module, its grammar, and
+/// its inputs and stored answers.
+#[derive(Debug)]
+struct WatchedDay {
+    module: PathBuf,
+    grammar: PathBuf,
+    data_dir: PathBuf,
+}
+
+impl WatchedDay {
+    fn contains(&self, path: &Path) -> bool {
+        path == self.module || path == self.grammar || path.starts_with(&self.data_dir)
+    }
+}
+
+/// Rebuilds the runner and re-solves `day` for its example and real inputs
+/// every time the day's source, grammar, or data changes. Runs until
+/// interrupted.
+pub fn watch(
+    registry: &Registry,
+    data_dir: &Path,
+    day: u16,
+    problem_part: Option<Part>,
+) -> Result<ExitCode> {
+    let workspace = workspace_dir().context("--watch must be run from within the workspace")?;
+    // Resolve the binary up front: once cargo replaces it, the path of the
+    // running executable no longer refers to the new build.
+    let exe = env::current_exe().context("failed to locate the runner binary")?;
+    let crate_dir = workspace
+        .join("years")
+        .join(format!("aoc_{}", registry.year()));
+    let day_dir = registry.day_dir(data_dir, day);
+    let day_files = WatchedDay {
+        module: crate_dir.join("src").join(format!("d{day}.rs")),
+        grammar: crate_dir.join("grammars").join(format!("d{day}.pest")),
+        data_dir: fs::canonicalize(&day_dir).unwrap_or(day_dir),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    // Watch directories rather than files so that editors which save by
+    // replacing a file don't silently end the watch.
+    for (dir, mode) in [
+        (crate_dir.join("src"), RecursiveMode::NonRecursive),
+        (crate_dir.join("grammars"), RecursiveMode::NonRecursive),
+        (day_files.data_dir.clone(), RecursiveMode::Recursive),
+    ] {
+        if dir.is_dir() {
+            watcher
+                .watch(&dir, mode)
+                .with_context(|| format!("failed to watch {}", dir.display()))?;
+        }
+    }
+
+    loop {
+        rerun(
+            &workspace,
+            &exe,
+            registry.year(),
+            data_dir,
+            day,
+            problem_part,
+        );
+        println!();
+        let mut paths = vec![day_files.module.display().to_string()];
+        if day_files.grammar.exists() {
+            paths.push(day_files.grammar.display().to_string());
+        }
+        paths.push(day_files.data_dir.display().to_string());
+        println!("watching {} for changes", paths.join(", "));
+        wait_for_change(&receiver, &day_files)?;
+    }
+}
+
+fn is_change(kind: EventKind) -> bool {
+    match kind {
+        EventKind::Access(AccessKind::Close(AccessMode::Write))
+        | EventKind::Create(_)
+        | EventKind::Remove(_) => true,
+        EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => false,
+    }
+}
+
+fn wait_for_change(
+    receiver: &Receiver<notify::Result<Event>>,
+    day_files: &WatchedDay,
+) -> Result<()> {
+    loop {
+        let event = receiver.recv().context("file watcher stopped")??;
+        if is_change(event.kind) && event.paths.iter().any(|path| day_files.contains(path)) {
+            break;
+        }
+    }
+    while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+    Ok(())
+}
+
+/// Arguments to `cargo` that rebuild `exe` with the profile it was built with.
+fn build_args(exe: &Path) -> Result<Vec<String>> {
+    let name = exe
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .context("invalid runner binary name")?;
+    let profile = exe
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str)
+        .unwrap_or("debug");
+
+    let mut args: Vec<String> = ["build", "--quiet", "--package", name, "--bin", name]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    match profile {
+        "debug" => {}
+        "release" => args.push("--release".to_string()),
+        profile => args.extend(["--profile".to_string(), profile.to_string()]),
+    }
+    Ok(args)
+}
+
+fn rebuild(workspace: &Path, exe: &Path) -> Result<()> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .args(build_args(exe)?)
+        .current_dir(workspace)
+        .status()
+        .context("failed to run cargo")?;
+    if !status.success() {
+        bail!("build failed");
+    }
+    Ok(())
+}
+
+fn solve(
+    exe: &Path,
+    year: i32,
+    data_dir: &Path,
+    day: u16,
+    problem_part: Option<Part>,
+    example: bool,
+) -> Result<Report> {
+    let mut command = Command::new(exe);
+    command
+        .arg("--year")
+        .arg(year.to_string())
+        .arg("--data-dir")
+        .arg(data_dir)
+        .args(["--format", "json"])
+        .arg(day.to_string());
+    if let Some(part) = problem_part {
+        command.arg(part.to_string());
+    }
+    if example {
+        command.arg("--example");
+    }
+    let output = command
+        .output()
+        .with_context(|| format!("failed to run {}", exe.display()))?;
+    serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "unexpected output from {}: {}",
+            exe.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })
+}
+
+fn input_name(example: bool) -> String {
+    if example {
+        "example".to_string()
+    } else {
+        "real".to_string()
+    }
+}
+
+fn report_row(run: &RunReport) -> [String; 7] {
+    let status = match &run.error {
+        Some(error) => format!("{} {}", run.verdict.symbol(), error.message),
+        None => format!("{} {}", run.verdict.symbol(), run.verdict),
+    };
+    [
+        input_name(run.example),
+        format!("P{}", run.part),
+        run.answer.as_ref().map(Answer::inline).unwrap_or_default(),
+        run.expected
+            .as_ref()
+            .map(Answer::inline)
+            .unwrap_or_default(),
+        run.parse_ns
+            .map(|ns| format_duration(Duration::from_nanos(ns)))
+            .unwrap_or_default(),
+        format_duration(Duration::from_nanos(run.elapsed_ns)),
+        status,
+    ]
+}
+
+fn rerun(
+    workspace: &Path,
+    exe: &Path,
+    year: i32,
+    data_dir: &Path,
+    day: u16,
+    problem_part: Option<Part>,
+) {
+    println!();
+    println!("[{}] rebuilding day {day}", Local::now().format("%H:%M:%S"));
+    if let Err(err) = rebuild(workspace, exe) {
+        println!("✗ {err:#}");
+        return;
+    }
+
+    let mut rows = vec![];
+    for example in [true, false] {
+        match solve(exe, year, data_dir, day, problem_part, example) {
+            Ok(Report::Runs { runs }) => rows.extend(runs.iter().map(report_row)),
+            Ok(Report::Error { error }) => rows.push(failed_row(example, &error.message)),
+            Err(err) => rows.push(failed_row(example, &format!("{err:#}"))),
+        }
+    }
+    print_table(
+        [
+            "input", "part", "answer", "expected", "parse", "time", "status",
+        ],
+        &rows,
+    );
+}
+
+fn failed_row(example: bool, message: &str) -> [String; 7] {
+    [
+        input_name(example),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format!("✗ {message}"),
+    ]
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_args() {
+        assert_eq!(
+            vec![
+                "build",
+                "--quiet",
+                "--package",
+                "aoc",
+                "--bin",
+                "aoc",
+                "--release"
+            ],
+            build_args(Path::new("/aoc_rs/target/release/aoc")).unwrap()
+        );
+        assert_eq!(
+            vec![
+                "build",
+                "--quiet",
+                "--package",
+                "aoc_2024",
+                "--bin",
+                "aoc_2024"
+            ],
+            build_args(Path::new("/aoc_rs/target/debug/aoc_2024")).unwrap()
+        );
+        assert_eq!(
+            vec![
+                "build",
+                "--quiet",
+                "--package",
+                "aoc",
+                "--bin",
+                "aoc",
+                "--profile",
+                "bench"
+            ],
+            build_args(Path::new("/aoc_rs/target/bench/aoc")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_watched_day() {
+        let day_files = WatchedDay {
+            module: PathBuf::from("/aoc_rs/years/aoc_2024/src/d4.rs"),
+            grammar: PathBuf::from("/aoc_rs/years/aoc_2024/grammars/d4.pest"),
+            data_dir: PathBuf::from("/aoc_rs/data/2024/04"),
+        };
+        assert!(day_files.contains(Path::new("/aoc_rs/years/aoc_2024/src/d4.rs")));
+        assert!(day_files.contains(Path::new("/aoc_rs/data/2024/04/example_1.txt")));
+        assert!(!day_files.contains(Path::new("/aoc_rs/years/aoc_2024/src/d3.rs")));
+        assert!(!day_files.contains(Path::new("/aoc_rs/data/2024/14/input.txt")));
+    }
+}
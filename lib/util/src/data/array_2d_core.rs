@@ -1,8 +1,8 @@
-use std::{fmt, path::Path};
+use std::{fmt, io::BufRead};
 
 use anyhow::{anyhow, bail, Context, Result};
 
-use crate::io::read_with_callback;
+use crate::io::for_each_line;
 
 use super::TwoDimensionalArray;
 
@@ -13,10 +13,10 @@ pub(crate) struct Array2dCore<T: Clone> {
 }
 
 impl Array2dCore<char> {
-    pub(crate) fn read_chars(path: &Path) -> Result<Self> {
+    pub(crate) fn read_chars<R: BufRead>(reader: R) -> Result<Self> {
         let mut values: Vec<Vec<char>> = vec![];
 
-        read_with_callback(path, &mut |line| {
+        for_each_line(reader, &mut |line| {
             let row: Vec<char> = line.as_str().chars().collect();
             values.push(row);
             Ok(())
@@ -29,14 +29,14 @@ impl Array2dCore<char> {
 }
 
 impl<T: Clone> Array2dCore<T> {
-    pub(crate) fn read_generic_from_path<F: FnMut(&str) -> Result<T>>(
-        path: &Path,
+    pub(crate) fn read_generic<R: BufRead, F: FnMut(&str) -> Result<T>>(
+        reader: R,
         sep: &str,
         mut map: F,
     ) -> Result<Self> {
         let mut values: Vec<Vec<T>> = vec![];
 
-        read_with_callback(path, &mut |line| {
+        for_each_line(reader, &mut |line| {
             let row: Result<Vec<T>> = line.split(sep).map(&mut map).collect();
             let row = row.context(format!("invalid row {line}"))?;
             values.push(row);
@@ -140,21 +140,25 @@ impl<T: Clone + fmt::Display> fmt::Display for Array2dCore<T> {
 
 macro_rules! typed_readers {
     ($ident:ident) => {
-        impl $ident<i64> {
-            pub fn read_from_path(path: &Path) -> Result<$ident<i64>> {
-                $ident::read_generic_from_path(path, " ", |x| {
-                    x.parse::<i64>().context("invalid i64")
-                })
-                .context("failed to parse input file")
+        crate::data::array_2d_core::typed_readers!($ident, i64);
+        crate::data::array_2d_core::typed_readers!($ident, u64);
+    };
+    ($ident:ident, $type:ty) => {
+        impl $ident<$type> {
+            pub fn read_from_path(path: &Path) -> Result<$ident<$type>> {
+                Self::read_from_input(&InputSource::from(path))
+            }
+
+            pub fn read_from_input(input: &InputSource) -> Result<$ident<$type>> {
+                Self::read_from_reader(input.reader()?)
+                    .with_context(|| format!("failed to parse {input}"))
             }
-        }
 
-        impl $ident<u64> {
-            pub fn read_from_path(path: &Path) -> Result<$ident<u64>> {
-                $ident::read_generic_from_path(path, " ", |x| {
-                    x.parse::<u64>().context("invalid i64")
+            pub fn read_from_reader<R: BufRead>(reader: R) -> Result<$ident<$type>> {
+                $ident::read_generic(reader, " ", |x| {
+                    x.parse::<$type>()
+                        .context(concat!("invalid ", stringify!($type)))
                 })
-                .context("failed to parse input file")
             }
         }
     };
@@ -1,8 +1,9 @@
-use std::{fmt, path::Path};
+use std::{fmt, io::BufRead, path::Path};
 
 use anyhow::{anyhow, Context, Result};
 
 use super::{array_2d_core::Array2dCore, TwoDimensionalArray};
+use crate::io::InputSource;
 
 #[derive(Debug, Clone)]
 pub struct Matrix<T: Clone> {
@@ -12,7 +13,15 @@ pub struct Matrix<T: Clone> {
 
 impl Matrix<char> {
     pub fn read_from_path(path: &Path) -> Result<Self> {
-        let inner = Array2dCore::<char>::read_chars(path)?;
+        Self::read_from_input(&InputSource::from(path))
+    }
+
+    pub fn read_from_input(input: &InputSource) -> Result<Self> {
+        Self::read_from_reader(input.reader()?).with_context(|| format!("failed to parse {input}"))
+    }
+
+    pub fn read_from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let inner = Array2dCore::<char>::read_chars(reader)?;
         let n_cols = inner
             .n_cols_if_uniform()
             .ok_or(anyhow!("rows are not of uniform length"))?;
@@ -29,7 +38,16 @@ impl<T: Clone> Matrix<T> {
         sep: &str,
         map: F,
     ) -> Result<Self> {
-        let inner = Array2dCore::read_generic_from_path(path, sep, map)?;
+        Self::read_generic(InputSource::from(path).reader()?, sep, map)
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn read_generic<R: BufRead, F: FnMut(&str) -> Result<T>>(
+        reader: R,
+        sep: &str,
+        map: F,
+    ) -> Result<Self> {
+        let inner = Array2dCore::read_generic(reader, sep, map)?;
         let n_cols = inner
             .n_cols_if_uniform()
             .ok_or(anyhow!("rows are not of uniform length"))?;
@@ -1,8 +1,9 @@
-use std::{fmt, path::Path};
+use std::{fmt, io::BufRead, path::Path};
 
 use anyhow::{Context, Result};
 
 use super::{array_2d_core::Array2dCore, TwoDimensionalArray};
+use crate::io::InputSource;
 
 #[derive(Debug, Clone)]
 pub struct Table<T: Clone> {
@@ -10,13 +11,13 @@ pub struct Table<T: Clone> {
 }
 
 impl<T: Clone> Table<T> {
-    pub(crate) fn read_generic_from_path<F: FnMut(&str) -> Result<T>>(
-        path: &Path,
+    pub(crate) fn read_generic<R: BufRead, F: FnMut(&str) -> Result<T>>(
+        reader: R,
         sep: &str,
         map: F,
     ) -> Result<Self> {
         Ok(Self {
-            inner: Array2dCore::read_generic_from_path(path, sep, map)?,
+            inner: Array2dCore::read_generic(reader, sep, map)?,
         })
     }
 
@@ -27,8 +28,16 @@ impl<T: Clone> Table<T> {
 
 impl Table<char> {
     pub fn read_from_path(path: &Path) -> Result<Self> {
+        Self::read_from_input(&InputSource::from(path))
+    }
+
+    pub fn read_from_input(input: &InputSource) -> Result<Self> {
+        Self::read_from_reader(input.reader()?).with_context(|| format!("failed to parse {input}"))
+    }
+
+    pub fn read_from_reader<R: BufRead>(reader: R) -> Result<Self> {
         Ok(Self {
-            inner: Array2dCore::<char>::read_chars(path)?,
+            inner: Array2dCore::<char>::read_chars(reader)?,
         })
     }
 }
@@ -65,4 +74,15 @@ mod tests {
         let mat = Table::new(values).unwrap();
         iter_direction(&mat);
     }
+
+    #[test]
+    fn test_read_from_input() {
+        let table = Table::<i64>::read_from_input(&InputSource::text("1 2 3\n4 5\n")).unwrap();
+        assert_eq!(
+            vec![vec![1, 2, 3], vec![4, 5]],
+            table.iter().cloned().collect::<Vec<_>>()
+        );
+        assert!(Table::<i64>::read_from_input(&InputSource::text("1 x\n")).is_err());
+        assert!(Table::<char>::read_from_path(Path::new("no/such/input.txt")).is_err());
+    }
 }
@@ -1,9 +1,116 @@
 use std::{
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+    convert::Infallible,
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
+use anyhow::{Context, Result};
+
+/// Where puzzle input is read from: a file, standard input, or an in-memory
+/// string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl InputSource {
+    #[must_use]
+    pub fn text(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+
+    /// Reads standard input into memory so that the input can be read more
+    /// than once. Other sources are returned as-is.
+    pub fn buffered(self) -> Result<Self> {
+        match self {
+            Self::Stdin => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .context("failed to read stdin")?;
+                Ok(Self::Text(text))
+            }
+            source => Ok(source),
+        }
+    }
+
+    pub fn reader(&self) -> Result<Box<dyn BufRead + '_>> {
+        match self {
+            Self::Path(path) => {
+                let file = File::open(path)
+                    .with_context(|| format!("failed to open {}", path.display()))?;
+                Ok(Box::new(io::BufReader::new(file)))
+            }
+            Self::Stdin => Ok(Box::new(io::stdin().lock())),
+            Self::Text(text) => Ok(Box::new(text.as_bytes())),
+        }
+    }
+
+    pub fn read_to_string(&self) -> Result<String> {
+        match self {
+            Self::Path(path) => fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display())),
+            Self::Stdin | Self::Text(_) => {
+                let mut text = String::new();
+                self.reader()?
+                    .read_to_string(&mut text)
+                    .with_context(|| format!("failed to read {self}"))?;
+                Ok(text)
+            }
+        }
+    }
+
+    pub fn read_lines(&self) -> Result<Vec<String>> {
+        let mut lines = vec![];
+        for_each_line(self.reader()?, &mut |line| {
+            lines.push(line);
+            Ok(())
+        })
+        .with_context(|| format!("failed to read {self}"))?;
+        Ok(lines)
+    }
+}
+
+impl From<&Path> for InputSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<PathBuf> for InputSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Infallible;
+
+    /// Parses a command line argument, where `-` means standard input.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "-" {
+            Ok(Self::Stdin)
+        } else {
+            Ok(Self::Path(PathBuf::from(s)))
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::Text(_) => write!(f, "<text>"),
+        }
+    }
+}
+
 pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where
     P: AsRef<Path>,
@@ -13,23 +120,42 @@ where
 }
 
 pub fn read_lines_to_vec(path: &Path) -> anyhow::Result<Vec<String>> {
-    let mut lines_vec: Vec<String> = vec![];
-    if let Ok(lines) = read_lines(path) {
-        for line in lines.map_while(Result::ok) {
-            lines_vec.push(line);
-        }
-    }
-    Ok(lines_vec)
+    InputSource::from(path).read_lines()
 }
 
 pub fn read_with_callback<F: FnMut(String) -> anyhow::Result<()>>(
     path: &Path,
     map: &mut F,
 ) -> anyhow::Result<()> {
-    if let Ok(lines) = read_lines(path) {
-        for line in lines.map_while(Result::ok) {
-            map(line)?;
-        }
+    for_each_line(InputSource::from(path).reader()?, map)
+        .with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Calls `map` with each line of `reader`, stopping at the first error.
+pub fn for_each_line<R: BufRead, F: FnMut(String) -> anyhow::Result<()>>(
+    reader: R,
+    map: &mut F,
+) -> anyhow::Result<()> {
+    for line in reader.lines() {
+        map(line?)?;
     }
     Ok(())
 }
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_source() {
+        let input = InputSource::text("1 2\n3 4\n");
+        assert_eq!(vec!["1 2", "3 4"], input.read_lines().unwrap());
+        assert_eq!("1 2\n3 4\n", input.read_to_string().unwrap());
+
+        assert_eq!(InputSource::Stdin, "-".parse().unwrap());
+        let missing: InputSource = "no/such/input.txt".parse().unwrap();
+        assert!(missing.read_lines().is_err());
+        assert!(read_lines_to_vec(Path::new("no/such/input.txt")).is_err());
+    }
+}
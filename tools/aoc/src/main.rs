@@ -0,0 +1,8 @@
+#![deny(clippy::all, clippy::pedantic, clippy::panic, clippy::unwrap_used)]
+#![allow(clippy::missing_errors_doc, clippy::module_name_repetitions)]
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    cli::run_years(&[aoc_2024::registry()])
+}
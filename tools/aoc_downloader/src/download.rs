@@ -0,0 +1,285 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use cli::{
+    answers::Answers,
+    util::{example_dir_for_year_and_day, file_path, INPUT_FILE},
+};
+use reqwest::StatusCode;
+
+use crate::{
+    puzzle::{parse_examples, Example},
+    session::Session,
+};
+
+/// The AOC website, unless `base_url` is configured.
+pub const BASE_AOC_URL: &str = "https://adventofcode.com";
+
+/// A response from the AOC website that isn't a puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadError {
+    /// The session cookie is missing, invalid, or expired
+    Unauthorized(StatusCode),
+    /// The puzzle hasn't unlocked yet
+    NotUnlocked,
+    /// Too many requests were made too quickly
+    RateLimited,
+    /// The website failed to handle the request
+    ServerError(StatusCode),
+    /// Any other response that isn't a puzzle input
+    Unexpected(StatusCode),
+}
+
+impl DownloadError {
+    /// Classifies a response from the input endpoint, returning `None` if it
+    /// holds a puzzle input. Some failures are only recognizable by their
+    /// body, such as a request without a valid cookie.
+    #[must_use]
+    pub fn classify(status: StatusCode, body: &str) -> Option<Self> {
+        if body.contains("Please log in") {
+            return Some(Self::Unauthorized(status));
+        }
+        match status {
+            status if status.is_success() => None,
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Some(Self::Unauthorized(status))
+            }
+            StatusCode::NOT_FOUND => Some(Self::NotUnlocked),
+            StatusCode::TOO_MANY_REQUESTS => Some(Self::RateLimited),
+            status if status.is_server_error() => Some(Self::ServerError(status)),
+            status => Some(Self::Unexpected(status)),
+        }
+    }
+
+    /// Exit code of the downloader when it fails with this error.
+    #[must_use]
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Unauthorized(_) => 3,
+            Self::NotUnlocked => 4,
+            Self::RateLimited => 5,
+            Self::ServerError(_) => 6,
+            Self::Unexpected(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthorized(status) => write!(
+                f,
+                "the session cookie was rejected ({status}), it may have expired: log in to \
+                 the AOC website again and copy the new session cookie"
+            ),
+            Self::NotUnlocked => write!(f, "the puzzle hasn't unlocked yet"),
+            Self::RateLimited => write!(
+                f,
+                "the AOC website is limiting requests, wait a while before trying again"
+            ),
+            Self::ServerError(status) => write!(
+                f,
+                "the AOC website failed to respond ({status}), try again later"
+            ),
+            Self::Unexpected(status) => {
+                write!(f, "unexpected response from the AOC website ({status})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// Downloads the input for `day` of `year`, failing with a [`DownloadError`]
+/// when the website responds with anything else.
+pub fn perform_request(session: &Session, year: i32, day: u16) -> Result<String> {
+    session.get(&format!("/{year}/day/{day}/input"))
+}
+
+/// Replaces the input at `path` with `data`. The data is written to a
+/// temporary file first, so a failed write never leaves a truncated input
+/// behind.
+pub(crate) fn write_input(path: &Path, data: &str) -> Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, data)
+        .with_context(|| format!("failed to write {}", temp_path.display()))?;
+    fs::rename(&temp_path, path).with_context(|| format!("failed to replace {}", path.display()))
+}
+
+/// Downloads the input for `day` and saves it as the day's `input.txt`,
+/// which both parts read. An existing input is only replaced once a valid
+/// input was downloaded.
+pub fn download(session: &Session, output_dir: &Path, year: i32, day: u16) -> Result<PathBuf> {
+    let example_dir = example_dir_for_year_and_day(output_dir, year, day);
+    let data = perform_request(session, year, day)
+        .with_context(|| format!("failed to download the input for day {day} of {year}"))?;
+
+    fs::create_dir_all(&example_dir).context("failed to create output directory")?;
+    let path = example_dir.join(INPUT_FILE);
+    write_input(&path, &data)?;
+    Ok(path)
+}
+
+/// Downloads the puzzle page for `day` and saves the examples it describes
+/// as the day's example files, along with their answers. Examples and answers
+/// that were already saved are kept, since they may have been edited by hand.
+/// Returns the files that were written.
+pub fn download_examples(
+    session: &Session,
+    output_dir: &Path,
+    year: i32,
+    day: u16,
+) -> Result<Vec<PathBuf>> {
+    let html = session
+        .get(&format!("/{year}/day/{day}"))
+        .with_context(|| format!("failed to download the puzzle for day {day} of {year}"))?;
+    let examples = parse_examples(&html)?;
+    if examples.is_empty() {
+        bail!("found no puzzle description for day {day} of {year}");
+    }
+
+    let example_dir = example_dir_for_year_and_day(output_dir, year, day);
+    fs::create_dir_all(&example_dir).context("failed to create output directory")?;
+    save_examples(&example_dir, &examples)
+}
+
+fn save_examples(example_dir: &Path, examples: &[Example]) -> Result<Vec<PathBuf>> {
+    let mut saved = vec![];
+    let mut answers = Answers::read(example_dir)?;
+    let mut answers_changed = false;
+    let mut previous: Option<(PathBuf, &str)> = None;
+    for example in examples {
+        let path = file_path(example_dir, example.part, true)?;
+        let input = example
+            .input
+            .as_deref()
+            .or(previous.as_ref().map(|(_, input)| *input));
+        if let Some(input) = input {
+            if path.symlink_metadata().is_err() {
+                match &previous {
+                    Some((previous_path, previous_input)) if *previous_input == input => {
+                        link_example(previous_path, &path)?;
+                    }
+                    _ => write_input(&path, input)?,
+                }
+                saved.push(path.clone());
+            }
+            previous = Some((path, input));
+        }
+        if let Some(answer) = &example.answer {
+            if answers.get(example.part, true).is_none() {
+                answers.set(example.part, true, answer.clone());
+                answers_changed = true;
+            }
+        }
+    }
+    if answers_changed {
+        answers.write(example_dir)?;
+        saved.push(Answers::path(example_dir));
+    }
+    Ok(saved)
+}
+
+/// Makes the example at `path` share the example at `target`, which is in the
+/// same directory.
+#[cfg(unix)]
+fn link_example(target: &Path, path: &Path) -> Result<()> {
+    let name = target.file_name().unwrap_or_default();
+    std::os::unix::fs::symlink(name, path)
+        .with_context(|| format!("failed to link {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn link_example(target: &Path, path: &Path) -> Result<()> {
+    fs::copy(target, path).with_context(|| format!("failed to copy to {}", path.display()))?;
+    Ok(())
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use cli::part::Part;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let classify = |code: u16, body: &str| {
+            DownloadError::classify(StatusCode::from_u16(code).unwrap(), body)
+        };
+        assert_eq!(None, classify(200, "1 2\n3 4\n"));
+        assert_eq!(
+            Some(DownloadError::Unauthorized(StatusCode::BAD_REQUEST)),
+            classify(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+            )
+        );
+        assert_eq!(
+            Some(DownloadError::Unauthorized(StatusCode::OK)),
+            classify(200, "Please log in to get your puzzle input.")
+        );
+        assert_eq!(
+            Some(DownloadError::NotUnlocked),
+            classify(
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!"
+            )
+        );
+        assert_eq!(Some(DownloadError::RateLimited), classify(429, ""));
+        assert_eq!(
+            Some(DownloadError::ServerError(StatusCode::BAD_GATEWAY)),
+            classify(502, "")
+        );
+        assert_eq!(
+            Some(DownloadError::Unexpected(StatusCode::IM_A_TEAPOT)),
+            classify(418, "")
+        );
+    }
+
+    #[test]
+    fn test_write_input() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(INPUT_FILE);
+        write_input(&path, "old\n").unwrap();
+        write_input(&path, "new\n").unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let entries = fs::read_dir(&dir).unwrap().count();
+        assert_eq!("new\n", contents);
+        assert_eq!(1, entries);
+    }
+
+    #[test]
+    fn test_save_examples() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path();
+        Answers::record(dir, Part::P2, true, "5".parse().unwrap()).unwrap();
+        let examples = parse_examples(include_str!("../tests/fixtures/2024_02.html")).unwrap();
+
+        let saved = save_examples(dir, &examples).unwrap();
+        let resaved = save_examples(dir, &examples).unwrap();
+        let example_1 = fs::read_to_string(dir.join("example_1.txt")).unwrap();
+        let example_2 = fs::read_to_string(dir.join("example_2.txt")).unwrap();
+        let answers = Answers::read(dir).unwrap();
+
+        assert_eq!(
+            vec![
+                dir.join("example_1.txt"),
+                dir.join("example_2.txt"),
+                Answers::path(dir)
+            ],
+            saved
+        );
+        assert!(resaved.is_empty());
+        assert_eq!(examples[0].input.as_deref(), Some(example_1.as_str()));
+        assert_eq!(example_1, example_2);
+        assert_eq!(Some(&"2".parse().unwrap()), answers.get(Part::P1, true));
+        assert_eq!(Some(&"5".parse().unwrap()), answers.get(Part::P2, true));
+    }
+}
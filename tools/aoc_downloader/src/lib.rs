@@ -0,0 +1,10 @@
+#![deny(clippy::all, clippy::pedantic, clippy::panic, clippy::unwrap_used)]
+#![allow(clippy::missing_errors_doc, clippy::module_name_repetitions)]
+
+//! Downloads puzzle inputs and examples from the AOC website, and submits
+//! answers to it.
+
+pub mod download;
+pub mod puzzle;
+pub mod session;
+pub mod submit;
@@ -1,102 +1,121 @@
 #![deny(clippy::all, clippy::pedantic, clippy::panic, clippy::unwrap_used)]
 #![allow(clippy::missing_errors_doc, clippy::module_name_repetitions)]
 
-use std::{
-    env,
-    fs::File,
-    io::{BufWriter, Write},
-    path::{Path, PathBuf},
-};
+use std::{io::ErrorKind, path::PathBuf, process::ExitCode};
 
-use anyhow::{anyhow, Context, Result};
-use clap::Parser;
-use cli::util::{example_dir_for_year_and_day, file_path};
-use reqwest::{
-    blocking::Client,
-    header::{HeaderMap, HeaderValue, COOKIE},
+use anyhow::{Context, Result};
+use aoc_downloader::{
+    download::{download, download_examples, DownloadError},
+    session::{session_cookie, Session},
+    submit::submit,
 };
-
-use cli::{
-    part::Part,
-    util::{current_year, default_data_dir},
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Parser, ValueEnum,
 };
-
-const BASE_AOC_URL: &str = "https://adventofcode.com";
+use cli::{answers::Answers, config::Config, part::Part, util::example_dir_for_year_and_day};
 
 /// AOC challenge data downloader
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Data directory
-    #[arg(short, long, default_value=default_data_dir().into_os_string())]
-    data_dir: PathBuf,
-    /// AOC challenge year
-    #[arg(short, long, default_value = current_year(), value_parser=clap::value_parser!(i32).range(2015..))]
-    year: i32,
+    /// Data directory, or `data_dir` from aoc.toml, or `data` in the workspace
+    /// root if omitted
+    #[arg(short, long)]
+    data_dir: Option<PathBuf>,
+    /// AOC challenge year, or `year` from aoc.toml, or the current AOC year if
+    /// omitted
+    #[arg(short, long, value_parser=clap::value_parser!(i32).range(2015..))]
+    year: Option<i32>,
     /// AOC session cookie
     #[arg(short, long)]
     aoc_session: Option<String>,
-    /// Problem part
-    #[arg(short, long, default_value_t = Part::P1)]
+    /// Problem part of the answer given to --record-answer or --submit
+    #[arg(short, long, default_value_t = Part::P1, value_parser = single_part())]
     problem_part: Part,
+    /// Record an accepted answer for the problem part instead of downloading
+    /// its input
+    #[arg(short, long)]
+    record_answer: Option<String>,
+    /// Save the examples from the puzzle page and their answers instead of
+    /// downloading the input
+    #[arg(short, long, conflicts_with = "record_answer")]
+    examples: bool,
+    /// Submit an answer for the problem part, recording it once it's accepted.
+    /// Answers that previous guesses show to be wrong aren't sent
+    #[arg(short, long, conflicts_with_all = ["record_answer", "examples"])]
+    submit: Option<String>,
     /// Problem day
     #[arg(value_parser=clap::value_parser!(u16).range(1..=25))]
     day: u16,
 }
 
-fn session_cookie(args: &Args) -> Option<String> {
-    match (&args.aoc_session, env::var("AOC_SESSION_COOKIE").ok()) {
-        (Some(args_session), _) => Some(String::from(args_session)),
-        (None, Some(env_session)) => Some(env_session),
-        (None, None) => None,
-    }
+/// Parses a part that a single answer is given for, which `Both` isn't.
+fn single_part() -> impl TypedValueParser<Value = Part> {
+    PossibleValuesParser::new(["P1", "P2"]).try_map(|part| Part::from_str(&part, false))
 }
 
-fn download(cookie: &str, output_dir: &Path, year: i32, day: u16, part: Part) -> Result<()> {
-    fn perform_request(cookie: &str, year: i32, day: u16) -> Result<String> {
-        let aoc_url = format!("{BASE_AOC_URL}/{year}/day/{day}/input");
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            COOKIE,
-            HeaderValue::from_str(&format!("session={cookie}")).expect("valid header"),
-        );
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .expect("bad reqwest builder");
-        match client.get(aoc_url).send() {
-            Ok(res) => Ok(res.text().context("bad content in aoc example")?),
-            Err(e) => Err(e).context("failed to download the requested example"),
+fn run() -> Result<()> {
+    match dotenv::dotenv() {
+        Err(dotenv::Error::Io(err)) if err.kind() == ErrorKind::NotFound => {}
+        result => {
+            result.context("failed to load dotenv environment")?;
         }
     }
-
-    fn write_response(example_dir: &Path, part: Part, data: &str) -> Result<()> {
-        let f = File::create(file_path(example_dir, part, false))
-            .context("failed to create output file")?;
-        let mut f = BufWriter::new(f);
-        f.write_all(data.as_bytes())
-            .context("failed to write example file")
+    let args = Args::parse();
+    let config = Config::global()?;
+    let data_dir = match args.data_dir {
+        Some(data_dir) => data_dir,
+        None => config.data_dir()?,
+    };
+    let year = args.year.unwrap_or_else(|| config.year());
+    if let Some(answer) = &args.record_answer {
+        let day_dir = example_dir_for_year_and_day(&data_dir, year, args.day);
+        std::fs::create_dir_all(&day_dir).context("failed to create output directory")?;
+        return Answers::record(&day_dir, args.problem_part, false, answer.parse()?);
     }
-
-    let example_dir = example_dir_for_year_and_day(output_dir, year, day);
-
-    std::fs::create_dir_all(&example_dir).context("failed to create output directory")?;
-    let data = perform_request(cookie, year, day)?;
-    write_response(&example_dir, part, &data)
+    let cookie = session_cookie(args.aoc_session, config)?;
+    let mut session = Session::new(&cookie, config.download_timeout())?;
+    if let Some(base_url) = &config.base_url {
+        session = session.with_base_url(base_url);
+    }
+    if let Some(answer) = &args.submit {
+        let day_dir = example_dir_for_year_and_day(&data_dir, year, args.day);
+        let outcome = submit(
+            &session,
+            &day_dir,
+            year,
+            args.day,
+            args.problem_part,
+            &answer.parse()?,
+        )?;
+        println!("{outcome}");
+        return Ok(());
+    }
+    if args.examples {
+        let saved = download_examples(&session, &data_dir, year, args.day)?;
+        if saved.is_empty() {
+            println!("the examples of day {} are already saved", args.day);
+        }
+        for path in saved {
+            println!("saved {}", path.display());
+        }
+        return Ok(());
+    }
+    let path = download(&session, &data_dir, year, args.day)?;
+    println!("saved {}", path.display());
+    Ok(())
 }
 
-fn main() -> Result<()> {
-    dotenv::dotenv().context("failed to load dotenv environment")?;
-    let args = Args::parse();
-    let cookie = session_cookie(&args).ok_or(anyhow!(
-        "you must specify aoc_session as a cli
-         arg or via the AOC_SESSION_COOKIE environment variable"
-    ))?;
-    download(
-        &cookie,
-        &args.data_dir,
-        args.year,
-        args.day,
-        args.problem_part,
-    )
+/// Exits with a distinct code for each way the AOC website can refuse a
+/// download, and 1 for any other error.
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            err.downcast_ref::<DownloadError>()
+                .map_or(ExitCode::FAILURE, |err| ExitCode::from(err.exit_code()))
+        }
+    }
 }
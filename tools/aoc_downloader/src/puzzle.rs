@@ -0,0 +1,169 @@
+use anyhow::Result;
+use cli::{answer::Answer, part::Part};
+use regex::Regex;
+
+/// The example of one part of a puzzle, as given in its description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub part: Part,
+    /// The example input, or `None` if the part reuses the previous part's
+    /// example
+    pub input: Option<String>,
+    /// The answer the description gives for the example
+    pub answer: Option<Answer>,
+}
+
+/// Extracts the examples from a puzzle page. Each part is described by its own
+/// `<article class="day-desc">`, whose first `<pre><code>` block is the example
+/// and whose last emphasized `<code>` is the example's answer. The second part
+/// is only on the page once the first one was solved.
+pub fn parse_examples(html: &str) -> Result<Vec<Example>> {
+    let article_pattern = Regex::new(r#"(?s)<article class="day-desc">(.*?)</article>"#)?;
+    let example_pattern = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>")?;
+    let answer_pattern =
+        Regex::new(r"(?s)<code><em>(.*?)</em></code>|<em><code>(.*?)</code></em>")?;
+
+    let parts = [Part::P1, Part::P2];
+    let examples = article_pattern
+        .captures_iter(html)
+        .zip(parts)
+        .map(|(article, part)| {
+            let article = &article[1];
+            let input = example_pattern.captures(article).map(|example| {
+                let mut input = decode_entities(&strip_tags(&example[1]));
+                if !input.ends_with('\n') {
+                    input.push('\n');
+                }
+                input
+            });
+            let answer = answer_pattern
+                .captures_iter(article)
+                .last()
+                .and_then(|answer| {
+                    let text = answer.get(1).or_else(|| answer.get(2))?.as_str();
+                    decode_entities(&strip_tags(text)).parse().ok()
+                });
+            Example {
+                part,
+                input,
+                answer,
+            }
+        })
+        .collect();
+    Ok(examples)
+}
+
+/// Removes the markup within a block, such as the `<em>` highlighting parts of
+/// an example.
+pub(crate) fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Replaces the character references the website escapes puzzle text with.
+pub(crate) fn decode_entities(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('&') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code = match entity.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        let (c, len) = decoded.unwrap_or(('&', 1));
+        text.push(c);
+        rest = &rest[len..];
+    }
+    text.push_str(rest);
+    text
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_example() {
+        let html = include_str!("../tests/fixtures/2024_02.html");
+        let examples = parse_examples(html).unwrap();
+        assert_eq!(
+            vec![
+                Example {
+                    part: Part::P1,
+                    input: Some(
+                        "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9\n"
+                            .to_string()
+                    ),
+                    answer: Some(Answer::Unsigned(2)),
+                },
+                Example {
+                    part: Part::P2,
+                    input: None,
+                    answer: Some(Answer::Unsigned(4)),
+                },
+            ],
+            examples
+        );
+    }
+
+    #[test]
+    fn test_separate_examples() {
+        let html = include_str!("../tests/fixtures/2024_03.html");
+        let examples = parse_examples(html).unwrap();
+        let inputs: Vec<_> = examples.iter().map(|x| x.input.as_deref()).collect();
+        assert_eq!(
+            vec![
+                Some("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))\n"),
+                Some("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))\n"),
+            ],
+            inputs
+        );
+        let answers: Vec<_> = examples.into_iter().map(|x| x.answer).collect();
+        assert_eq!(
+            vec![Some(Answer::Unsigned(161)), Some(Answer::Unsigned(48))],
+            answers
+        );
+    }
+
+    #[test]
+    fn test_unsolved_puzzle() {
+        let html = include_str!("../tests/fixtures/2024_02.html");
+        let (first_part, _) = html.split_once("<p>Your puzzle answer was").unwrap();
+        let examples = parse_examples(&format!("{first_part}</main>")).unwrap();
+        assert_eq!(1, examples.len());
+        assert_eq!(Some(Answer::Unsigned(2)), examples[0].answer);
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            "a<b> & \"c\" 'd'",
+            decode_entities("a&lt;b&gt; &amp; &quot;c&quot; &#39;d&#x27;")
+        );
+        assert_eq!("AT&T; &amp more", decode_entities("AT&T; &amp more"));
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use cli::config::Config;
+use reqwest::{
+    blocking::{Client, RequestBuilder},
+    header::{HeaderMap, HeaderValue, COOKIE},
+};
+
+use crate::download::{DownloadError, BASE_AOC_URL};
+
+/// The session cookie given on the command line, or the configured one.
+pub fn session_cookie(cookie: Option<String>, config: &Config) -> Result<String> {
+    cookie.or(config.session.clone()).ok_or(anyhow!(
+        "you must specify aoc_session as a cli arg, via the AOC_SESSION_COOKIE \
+         environment variable, or as session in aoc.toml"
+    ))
+}
+
+/// An HTTP client logged in to the AOC website with a session cookie.
+pub struct Session {
+    client: Client,
+    base_url: String,
+}
+
+impl Session {
+    pub fn new(cookie: &str, timeout: Option<Duration>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            COOKIE,
+            HeaderValue::from_str(&format!("session={cookie}"))
+                .context("the session cookie isn't a valid header value")?,
+        );
+        let mut builder = Client::builder().default_headers(headers);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        Ok(Self {
+            client: builder.build().context("failed to create an HTTP client")?,
+            base_url: BASE_AOC_URL.to_string(),
+        })
+    }
+
+    /// Sends requests to `base_url` rather than the AOC website.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetches `path`, failing with a [`DownloadError`] when the website
+    /// refuses the request.
+    pub fn get(&self, path: &str) -> Result<String> {
+        send(path, self.client.get(format!("{}{path}", self.base_url)))
+    }
+
+    /// Posts `form` to `path`, failing with a [`DownloadError`] when the
+    /// website refuses the request.
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let request = self
+            .client
+            .post(format!("{}{path}", self.base_url))
+            .form(form);
+        send(path, request)
+    }
+}
+
+fn send(path: &str, request: RequestBuilder) -> Result<String> {
+    let res = request
+        .send()
+        .with_context(|| format!("failed to request {path}"))?;
+    let status = res.status();
+    let body = res
+        .text()
+        .with_context(|| format!("bad content in {path}"))?;
+    match DownloadError::classify(status, &body) {
+        Some(err) => Err(err.into()),
+        None => Ok(body),
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use cli::{answer::Answer, answers::Answers, part::Part};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    puzzle::{decode_entities, strip_tags},
+    session::Session,
+};
+
+const GUESSES_FILE: &str = "guesses.json";
+
+/// How the website judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// A wrong answer the website gave no hint about
+    Wrong,
+}
+
+/// The website's response to a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The answer was judged, and no other answer is accepted until `wait`
+    /// has passed
+    Judged {
+        verdict: Verdict,
+        wait: Option<Duration>,
+    },
+    /// An answer was submitted too recently, so this one wasn't judged
+    TooSoon(Duration),
+    /// The part was already solved, so the answer wasn't judged
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// Reads the outcome from the page the website responds to an answer with.
+    pub fn parse(html: &str) -> Result<Self> {
+        let text = decode_entities(&strip_tags(html));
+        let wait_pattern = Regex::new(r"(?i)wait (one|\d+) minutes?")?;
+        let too_soon_pattern = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait")?;
+
+        let minutes = |value: &str| value.parse().unwrap_or(1);
+        let wait = wait_pattern
+            .captures(&text)
+            .map(|wait| Duration::from_secs(60 * minutes(&wait[1])));
+        if text.contains("That's the right answer") {
+            return Ok(Self::Judged {
+                verdict: Verdict::Correct,
+                wait: None,
+            });
+        }
+        if text.contains("That's not the right answer") {
+            let verdict = if text.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if text.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            };
+            return Ok(Self::Judged { verdict, wait });
+        }
+        if let Some(left) = too_soon_pattern.captures(&text) {
+            let minutes = left.get(1).map_or(0, |x| minutes(x.as_str()));
+            let seconds = minutes * 60 + left[2].parse::<u64>()?;
+            return Ok(Self::TooSoon(Duration::from_secs(seconds)));
+        }
+        if text.contains("You don't seem to be solving the right level") {
+            return Ok(Self::AlreadySolved);
+        }
+        bail!("unexpected response to the answer from the AOC website")
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Judged { verdict, wait } => {
+                match verdict {
+                    Verdict::Correct => write!(f, "that's the right answer")?,
+                    Verdict::TooHigh => write!(f, "that's not the right answer, it's too high")?,
+                    Verdict::TooLow => write!(f, "that's not the right answer, it's too low")?,
+                    Verdict::Wrong => write!(f, "that's not the right answer")?,
+                }
+                match wait {
+                    Some(wait) => write!(f, ", wait {} before trying again", format_wait(*wait)),
+                    None => Ok(()),
+                }
+            }
+            Self::TooSoon(wait) => write!(
+                f,
+                "an answer was submitted too recently, wait {} before trying again",
+                format_wait(*wait)
+            ),
+            Self::AlreadySolved => write!(f, "the part was already solved"),
+        }
+    }
+}
+
+fn format_wait(wait: Duration) -> String {
+    match (wait.as_secs() / 60, wait.as_secs() % 60) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}m"),
+        (minutes, seconds) => format!("{minutes}m {seconds}s"),
+    }
+}
+
+/// An answer that was submitted and judged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guess {
+    pub part: u8,
+    pub answer: Answer,
+    pub verdict: Verdict,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// The answers submitted for a single day, stored alongside its inputs as
+/// `guesses.json`, so that answers the website can only reject aren't sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuessLog {
+    #[serde(default)]
+    pub guesses: Vec<Guess>,
+    /// When the website accepts answers again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_until: Option<DateTime<Utc>>,
+}
+
+impl GuessLog {
+    #[must_use]
+    pub fn path(day_dir: &Path) -> PathBuf {
+        day_dir.join(GUESSES_FILE)
+    }
+
+    /// Reads the guesses stored in `day_dir`. A missing log is treated as
+    /// having no guesses.
+    pub fn read(day_dir: &Path) -> Result<Self> {
+        let path = Self::path(day_dir);
+        match fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data)
+                .with_context(|| format!("invalid guess log {}", path.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to read guess log {}", path.display()))
+            }
+        }
+    }
+
+    pub fn write(&self, day_dir: &Path) -> Result<()> {
+        let path = Self::path(day_dir);
+        let mut data =
+            serde_json::to_string_pretty(self).context("failed to serialize guess log")?;
+        data.push('\n');
+        fs::write(&path, data)
+            .with_context(|| format!("failed to write guess log {}", path.display()))
+    }
+
+    /// Fails if submitting `answer` for `part` at `now` can't be correct or
+    /// would be refused: while waiting out a cooldown, once the part is
+    /// solved, for an answer that was already rejected, or for a number
+    /// outside the bounds set by answers that were too high or too low.
+    pub fn check(&self, part: Part, answer: &Answer, now: DateTime<Utc>) -> Result<()> {
+        if let Some(wait) = self
+            .wait_until
+            .and_then(|wait_until| (wait_until - now).to_std().ok())
+            .filter(|wait| !wait.is_zero())
+        {
+            bail!(
+                "an answer was submitted too recently, wait {} before trying again",
+                format_wait(wait)
+            );
+        }
+        let guesses = || {
+            self.guesses
+                .iter()
+                .filter(move |guess| guess.part == part.value())
+        };
+        if let Some(guess) = guesses().find(|guess| guess.verdict == Verdict::Correct) {
+            bail!(
+                "part {} was already solved with {}",
+                part.value(),
+                guess.answer
+            );
+        }
+        if let Some(guess) = guesses().find(|guess| guess.answer == *answer) {
+            bail!("{answer} was already rejected on {}", guess.submitted_at);
+        }
+        let Some(value) = number(answer) else {
+            return Ok(());
+        };
+        let bound = |verdict: Verdict| {
+            guesses()
+                .filter(move |guess| guess.verdict == verdict)
+                .filter_map(|guess| number(&guess.answer))
+        };
+        if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| value >= *high) {
+            bail!("{answer} can't be right, since {high} was too high");
+        }
+        if let Some(low) = bound(Verdict::TooLow).max().filter(|low| value <= *low) {
+            bail!("{answer} can't be right, since {low} was too low");
+        }
+        Ok(())
+    }
+
+    /// Records how the website responded to `answer` for `part`.
+    pub fn record(&mut self, part: Part, answer: &Answer, outcome: Outcome, now: DateTime<Utc>) {
+        let wait = match outcome {
+            Outcome::Judged { verdict, wait } => {
+                self.guesses.push(Guess {
+                    part: part.value(),
+                    answer: answer.clone(),
+                    verdict,
+                    submitted_at: now,
+                });
+                wait
+            }
+            Outcome::TooSoon(wait) => Some(wait),
+            Outcome::AlreadySolved => None,
+        };
+        self.wait_until = wait
+            .and_then(|wait| TimeDelta::from_std(wait).ok())
+            .and_then(|wait| now.checked_add_signed(wait));
+    }
+}
+
+fn number(answer: &Answer) -> Option<i128> {
+    answer.to_string().parse().ok()
+}
+
+/// Submits `answer` for `part` of `day`, unless the guesses logged in
+/// `day_dir` show that it can't be right or wouldn't be judged. The response
+/// is logged, and a correct answer is recorded in the day's answers.
+pub fn submit(
+    session: &Session,
+    day_dir: &Path,
+    year: i32,
+    day: u16,
+    part: Part,
+    answer: &Answer,
+) -> Result<Outcome> {
+    if part == Part::Both {
+        bail!("answers are submitted one part at a time");
+    }
+    let mut log = GuessLog::read(day_dir)?;
+    log.check(part, answer, Utc::now())?;
+
+    let level = part.value().to_string();
+    let answer_text = answer.to_string();
+    let html = session
+        .post(
+            &format!("/{year}/day/{day}/answer"),
+            &[("level", &level), ("answer", &answer_text)],
+        )
+        .with_context(|| format!("failed to submit the answer for day {day} of {year}"))?;
+    let outcome = Outcome::parse(&html)?;
+
+    fs::create_dir_all(day_dir).context("failed to create output directory")?;
+    log.record(part, answer, outcome, Utc::now());
+    log.write(day_dir)?;
+    if let Outcome::Judged {
+        verdict: Verdict::Correct,
+        ..
+    } = outcome
+    {
+        Answers::record(day_dir, part, false, answer.clone())?;
+    }
+    Ok(outcome)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(message: &str) -> String {
+        format!("<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        let parse = |message: &str| Outcome::parse(&response(message)).unwrap();
+        assert_eq!(
+            Outcome::Judged {
+                verdict: Verdict::Correct,
+                wait: None
+            },
+            parse(
+                "That&apos;s the right answer!  You are <span class=\"day-success\">one gold \
+                 star</span> closer."
+            )
+        );
+        assert_eq!(
+            Outcome::Judged {
+                verdict: Verdict::TooLow,
+                wait: Some(Duration::from_secs(60))
+            },
+            parse(
+                "That's not the right answer; your answer is too low.  Please wait one minute \
+                 before trying again."
+            )
+        );
+        assert_eq!(
+            Outcome::Judged {
+                verdict: Verdict::Wrong,
+                wait: Some(Duration::from_secs(300))
+            },
+            parse(
+                "That's not the right answer.  Because you have guessed incorrectly 4 times on \
+                 this puzzle, please wait 5 minutes before trying again."
+            )
+        );
+        assert_eq!(
+            Outcome::TooSoon(Duration::from_secs(94)),
+            parse(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 34s left to wait."
+            )
+        );
+        assert_eq!(
+            Outcome::AlreadySolved,
+            parse("You don't seem to be solving the right level.  Did you already complete it?")
+        );
+        assert!(Outcome::parse(&response("Something else")).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let now = Utc::now();
+        let answer = |value: &str| value.parse::<Answer>().unwrap();
+        let judged = |verdict| Outcome::Judged {
+            verdict,
+            wait: None,
+        };
+        let mut log = GuessLog::default();
+        log.record(Part::P1, &answer("100"), judged(Verdict::TooHigh), now);
+        log.record(Part::P1, &answer("10"), judged(Verdict::TooLow), now);
+        log.record(Part::P1, &answer("50"), judged(Verdict::Wrong), now);
+
+        assert!(log.check(Part::P1, &answer("42"), now).is_ok());
+        assert!(log.check(Part::P1, &answer("50"), now).is_err());
+        assert!(log.check(Part::P1, &answer("100"), now).is_err());
+        assert!(log.check(Part::P1, &answer("150"), now).is_err());
+        assert!(log.check(Part::P1, &answer("10"), now).is_err());
+        assert!(log.check(Part::P2, &answer("150"), now).is_ok());
+
+        log.record(
+            Part::P1,
+            &answer("42"),
+            Outcome::TooSoon(Duration::from_secs(30)),
+            now,
+        );
+        assert!(log.check(Part::P2, &answer("150"), now).is_err());
+        let later = now + TimeDelta::seconds(30);
+        assert!(log.check(Part::P2, &answer("150"), later).is_ok());
+
+        log.record(Part::P1, &answer("42"), judged(Verdict::Correct), later);
+        assert!(log.check(Part::P1, &answer("43"), later).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+};
+
+use aoc_downloader::{
+    download::{download, DownloadError},
+    session::Session,
+};
+use cli::util::INPUT_FILE;
+use mockito::{Server, ServerGuard};
+use reqwest::StatusCode;
+use tempfile::tempdir;
+
+const INPUT: &str = "3   4\n4   3\n2   5\n";
+
+fn session(server: &ServerGuard) -> Session {
+    Session::new("abc", None)
+        .unwrap()
+        .with_base_url(&server.url())
+}
+
+/// Saves an input for day 1 of 2024, which a failed download must keep.
+fn save_input(data_dir: &Path) -> PathBuf {
+    let day_dir = data_dir.join("2024").join("01");
+    fs::create_dir_all(&day_dir).unwrap();
+    let path = day_dir.join(INPUT_FILE);
+    fs::write(&path, "old\n").unwrap();
+    path
+}
+
+/// Downloads day 1 of 2024 from a server that responds with `status` and
+/// `body`, returning the error and the input left behind.
+fn failed_download(status: usize, body: &str) -> (anyhow::Error, String) {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/2024/day/1/input")
+        .with_status(status)
+        .with_body(body)
+        .create();
+    let dir = tempdir().unwrap();
+    let path = save_input(dir.path());
+
+    let err = download(&session(&server), dir.path(), 2024, 1).unwrap_err();
+    let input = fs::read_to_string(path).unwrap();
+    mock.assert();
+    (err, input)
+}
+
+#[test]
+fn test_download() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/2024/day/1/input")
+        .match_header("cookie", "session=abc")
+        .with_body(INPUT)
+        .create();
+    let dir = tempdir().unwrap();
+    let path = save_input(dir.path());
+
+    let saved = download(&session(&server), dir.path(), 2024, 1).unwrap();
+    let input = fs::read_to_string(&path).unwrap();
+
+    mock.assert();
+    assert_eq!(path, saved);
+    assert_eq!(INPUT, input);
+}
+
+#[test]
+fn test_unauthorized() {
+    let (err, input) = failed_download(
+        400,
+        "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+    );
+    assert_eq!(
+        Some(&DownloadError::Unauthorized(StatusCode::BAD_REQUEST)),
+        err.downcast_ref()
+    );
+    assert_eq!("old\n", input);
+}
+
+#[test]
+fn test_not_unlocked() {
+    let (err, input) = failed_download(
+        404,
+        "Please don't repeatedly request this endpoint before it unlocks! The calendar \
+         countdown is synchronized with the server time; the link will be enabled on the \
+         calendar the instant this puzzle becomes available.\n",
+    );
+    assert_eq!(Some(&DownloadError::NotUnlocked), err.downcast_ref());
+    assert_eq!("old\n", input);
+}
+
+#[test]
+fn test_rate_limited() {
+    let (err, input) = failed_download(429, "");
+    assert_eq!(Some(&DownloadError::RateLimited), err.downcast_ref());
+    assert_eq!("old\n", input);
+}
+
+#[test]
+fn test_truncated_body() {
+    // The mock server always sends whole bodies, so this one closes the
+    // connection partway through the input it promised.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let _ = stream.read(&mut request).unwrap();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\ncontent-length: 1000\r\nconnection: close\r\n\r\n{INPUT}"
+        )
+        .unwrap();
+    });
+    let dir = tempdir().unwrap();
+    let path = save_input(dir.path());
+
+    let session = Session::new("abc", None).unwrap().with_base_url(&url);
+    let result = download(&session, dir.path(), 2024, 1);
+    let input = fs::read_to_string(path).unwrap();
+    server.join().unwrap();
+
+    assert!(format!("{:#}", result.unwrap_err()).contains("bad content"));
+    assert_eq!("old\n", input);
+}
+
+#[test]
+fn test_base_url_from_env() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/2024/day/1/input")
+        .with_status(404)
+        .create();
+    let dir = tempdir().unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_aoc_downloader"))
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("AOC_BASE_URL", server.url())
+        .args(["--year", "2024", "--aoc-session", "abc", "--data-dir"])
+        .arg(dir.path())
+        .arg("1")
+        .output()
+        .unwrap()
+        .status;
+
+    mock.assert();
+    assert_eq!(
+        Some(i32::from(DownloadError::NotUnlocked.exit_code())),
+        status.code()
+    );
+}
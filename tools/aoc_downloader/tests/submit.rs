@@ -0,0 +1,126 @@
+use std::{process::Command, time::Duration};
+
+use aoc_downloader::{
+    session::Session,
+    submit::{submit, GuessLog, Outcome, Verdict},
+};
+use cli::{answer::Answer, answers::Answers, part::Part};
+use mockito::{Matcher, Server};
+use tempfile::tempdir;
+
+const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  \
+                        Please wait one minute before trying again.</p></article>";
+
+fn answer(value: &str) -> Answer {
+    value.parse().unwrap()
+}
+
+#[test]
+fn test_submit_correct() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/2024/day/1/answer")
+        .match_header("cookie", "session=abc")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("level".into(), "2".into()),
+            Matcher::UrlEncoded("answer".into(), "42".into()),
+        ]))
+        .with_body(
+            "<article><p>That's the right answer!  You are one gold star closer.</p></article>",
+        )
+        .create();
+    let session = Session::new("abc", None)
+        .unwrap()
+        .with_base_url(&server.url());
+    let tmp = tempdir().unwrap();
+    let dir = tmp.path();
+
+    let outcome = submit(&session, dir, 2024, 1, Part::P2, &answer("42")).unwrap();
+    let answers = Answers::read(dir).unwrap();
+    let log = GuessLog::read(dir).unwrap();
+
+    mock.assert();
+    assert_eq!(
+        Outcome::Judged {
+            verdict: Verdict::Correct,
+            wait: None
+        },
+        outcome
+    );
+    assert_eq!(Some(&answer("42")), answers.get(Part::P2, false));
+    assert_eq!(1, log.guesses.len());
+    assert_eq!(None, log.wait_until);
+}
+
+#[test]
+fn test_submit_wrong() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/2024/day/2/answer")
+        .with_body(TOO_HIGH)
+        .expect(1)
+        .create();
+    let session = Session::new("abc", None)
+        .unwrap()
+        .with_base_url(&server.url());
+    let tmp = tempdir().unwrap();
+    let dir = tmp.path();
+
+    let outcome = submit(&session, dir, 2024, 2, Part::P1, &answer("500")).unwrap();
+    // Waiting out the cooldown doesn't make a larger answer worth sending.
+    let mut log = GuessLog::read(dir).unwrap();
+    log.wait_until = None;
+    log.write(dir).unwrap();
+    let resubmitted = submit(&session, dir, 2024, 2, Part::P1, &answer("600"));
+    let answers = Answers::read(dir).unwrap();
+
+    mock.assert();
+    assert_eq!(
+        Outcome::Judged {
+            verdict: Verdict::TooHigh,
+            wait: Some(Duration::from_secs(60))
+        },
+        outcome
+    );
+    assert!(resubmitted.is_err());
+    assert_eq!(None, answers.get(Part::P1, false));
+}
+
+#[test]
+fn test_submit_during_cooldown() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/2024/day/3/answer")
+        .with_body(
+            "<article><p>You gave an answer too recently; you have to wait after submitting an \
+             answer before trying again.  You have 45s left to wait.</p></article>",
+        )
+        .expect(1)
+        .create();
+    let session = Session::new("abc", None)
+        .unwrap()
+        .with_base_url(&server.url());
+    let tmp = tempdir().unwrap();
+    let dir = tmp.path();
+
+    let outcome = submit(&session, dir, 2024, 3, Part::P1, &answer("7")).unwrap();
+    let resubmitted = submit(&session, dir, 2024, 3, Part::P1, &answer("7"));
+    let log = GuessLog::read(dir).unwrap();
+
+    mock.assert();
+    assert_eq!(Outcome::TooSoon(Duration::from_secs(45)), outcome);
+    assert!(resubmitted.is_err());
+    assert!(log.guesses.is_empty());
+    assert!(log.wait_until.is_some());
+}
+
+#[test]
+fn test_submit_single_part() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc_downloader"))
+        .args(["1", "-p", "Both", "--submit", "42"])
+        .output()
+        .unwrap();
+    assert_eq!(Some(2), output.status.code());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[possible values: P1, P2]"), "{stderr}");
+}
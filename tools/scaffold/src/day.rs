@@ -0,0 +1,229 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use cli::{
+    part::Part,
+    util::{example_dir_for_year_and_day, file_path},
+};
+use regex::Regex;
+
+const DAY_TEMPLATE: &str = include_str!("../templates/day.rs.tmpl");
+const DAY_GRAMMAR_TEMPLATE: &str = include_str!("../templates/day_grammar.rs.tmpl");
+const TESTS_TEMPLATE: &str = include_str!("../templates/tests.rs.tmpl");
+const GRAMMAR_TEMPLATE: &str = include_str!("../templates/day.pest.tmpl");
+
+/// The last day of a year, whose puzzle only has a first part
+const FINAL_DAY: u16 = 25;
+
+pub fn year_dir(workspace_dir: &Path, year: i32) -> PathBuf {
+    workspace_dir.join("years").join(format!("aoc_{year}"))
+}
+
+/// Generates the module for `day`, registers it with the year's registry, and
+/// creates an empty example input for each of its parts. Nothing is
+/// overwritten: existing solution modules and grammars are an error, existing
+/// examples are left alone.
+pub fn scaffold(
+    workspace_dir: &Path,
+    data_dir: &Path,
+    year: i32,
+    day: u16,
+    grammar: bool,
+) -> Result<()> {
+    let crate_dir = year_dir(workspace_dir, year);
+    if !crate_dir.is_dir() {
+        bail!("no crate for {year} at {}", crate_dir.display());
+    }
+
+    let module = format!("d{day}");
+    let module_path = crate_dir.join("src").join(format!("{module}.rs"));
+    if module_path.exists() {
+        bail!("{} already exists", module_path.display());
+    }
+    let grammar_path = crate_dir.join("grammars").join(format!("{module}.pest"));
+    if grammar && grammar_path.exists() {
+        bail!("{} already exists", grammar_path.display());
+    }
+
+    let registry_path = registry_path(&crate_dir)?;
+    let source = fs::read_to_string(&registry_path)
+        .with_context(|| format!("failed to read {}", registry_path.display()))?;
+    let registered = register_day(&source, &module)
+        .with_context(|| format!("failed to register {module} in {}", registry_path.display()))?;
+
+    write_new(&module_path, &render_day(day, grammar))?;
+    if grammar {
+        fs::create_dir_all(crate_dir.join("grammars"))?;
+        write_new(&grammar_path, GRAMMAR_TEMPLATE)?;
+    }
+    fs::write(&registry_path, registered)
+        .with_context(|| format!("failed to write {}", registry_path.display()))?;
+    println!("registered {module} in {}", registry_path.display());
+
+    let example_dir = example_dir_for_year_and_day(data_dir, year, day);
+    fs::create_dir_all(&example_dir)
+        .with_context(|| format!("failed to create {}", example_dir.display()))?;
+    for part in parts(day) {
+        let path = file_path(&example_dir, *part, true)?;
+        if !path.exists() {
+            write_new(&path, "")?;
+        }
+    }
+    Ok(())
+}
+
+/// Finds the source file that invokes `cli::days!` for a year crate.
+fn registry_path(crate_dir: &Path) -> Result<PathBuf> {
+    for name in ["lib.rs", "main.rs"] {
+        let path = crate_dir.join("src").join(name);
+        if fs::read_to_string(&path).is_ok_and(|source| source.contains("days!(")) {
+            return Ok(path);
+        }
+    }
+    bail!("no days! registry found in {}", crate_dir.display())
+}
+
+/// Writes a file that must not exist yet.
+pub fn write_new(path: &Path, contents: &str) -> Result<()> {
+    File::create_new(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    println!("created {}", path.display());
+    Ok(())
+}
+
+fn parts(day: u16) -> &'static [Part] {
+    if day == FINAL_DAY {
+        &[Part::P1]
+    } else {
+        &[Part::P1, Part::P2]
+    }
+}
+
+fn render_day(day: u16, grammar: bool) -> String {
+    let template = if grammar {
+        DAY_GRAMMAR_TEMPLATE
+    } else {
+        DAY_TEMPLATE
+    };
+    let rendered = template
+        .replace("{{tests}}", TESTS_TEMPLATE)
+        .replace("{{day}}", &day.to_string());
+    let rendered = render_part2(&rendered, parts(day).contains(&Part::P2));
+    format!("{}\n", rendered.trim_end())
+}
+
+/// Keeps the lines between `{{#part2}}` and `{{/part2}}` for a day with a
+/// second part, and those between `{{^part2}}` and `{{/part2}}` otherwise.
+fn render_part2(template: &str, part2: bool) -> String {
+    let mut shown = true;
+    let mut lines = vec![];
+    for line in template.lines() {
+        match line.trim() {
+            "{{#part2}}" => shown = part2,
+            "{{^part2}}" => shown = !part2,
+            "{{/part2}}" => shown = true,
+            _ if shown => lines.push(line),
+            _ => {}
+        }
+    }
+    lines.join("\n")
+}
+
+/// Adds `module` to the `days!` invocation in `source`, keeping the modules
+/// in day order.
+fn register_day(source: &str, module: &str) -> Result<String> {
+    let pattern = Regex::new(r"days!\(\s*([^;]+?)\s*;([^)]*)\)")?;
+    let captures = pattern.captures(source).context("no days! invocation")?;
+    let year = &captures[1];
+    let mut modules: Vec<&str> = captures[2]
+        .split(',')
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .collect();
+    if modules.contains(&module) {
+        bail!("{module} is already registered");
+    }
+    modules.push(module);
+    modules.sort_by_key(|module| {
+        (
+            module
+                .trim_start_matches('d')
+                .parse::<u16>()
+                .unwrap_or(u16::MAX),
+            *module,
+        )
+    });
+
+    let invocation = format!("days!({year}; {})", modules.join(", "));
+    let range = captures.get(0).context("no days! invocation")?.range();
+    Ok(format!(
+        "{}{invocation}{}",
+        &source[..range.start],
+        &source[range.end..]
+    ))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_write_new() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("d1.rs");
+        write_new(&path, "new").unwrap();
+        let overwritten = write_new(&path, "again");
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(overwritten.is_err());
+        assert_eq!("new", contents);
+    }
+
+    #[test]
+    fn test_register_day() {
+        let source = "const YEAR: i32 = 2024;\n\ncli::days!(YEAR; d1, d2, d10);\n";
+        assert_eq!(
+            "const YEAR: i32 = 2024;\n\ncli::days!(YEAR; d1, d2, d3, d10);\n",
+            register_day(source, "d3").unwrap()
+        );
+        assert_eq!(
+            "cli::days!(YEAR; d1);",
+            register_day("cli::days!(YEAR;);", "d1").unwrap()
+        );
+        assert!(register_day(source, "d2").is_err());
+        assert!(register_day("fn main() {}", "d1").is_err());
+    }
+
+    #[test]
+    fn test_render_day() {
+        for grammar in [false, true] {
+            let rendered = render_day(7, grammar);
+            assert!(!rendered.contains("{{"));
+            assert!(rendered.contains("const DAY: u16 = 7;"));
+            assert!(rendered.contains("cli::day_tests!"));
+        }
+        assert!(render_day(7, true).contains("grammars/d7.pest"));
+    }
+
+    #[test]
+    fn test_render_final_day() {
+        for grammar in [false, true] {
+            let rendered = render_day(FINAL_DAY, grammar);
+            assert!(!rendered.contains("{{"));
+            assert!(rendered.contains("const PARTS: &'static [Part] = &[Part::P1];"));
+            assert!(rendered.contains("part::Part"));
+            assert!(!rendered.contains("part2"));
+            assert!(!rendered.contains("solve_p2"));
+            assert!(!rendered.contains("example_2"));
+            assert!(!rendered.contains("part_2"));
+        }
+        assert!(!render_day(7, false).contains("PARTS"));
+    }
+}
@@ -0,0 +1,79 @@
+#![deny(clippy::all, clippy::pedantic, clippy::panic, clippy::unwrap_used)]
+#![allow(clippy::missing_errors_doc, clippy::module_name_repetitions)]
+
+mod day;
+mod migrate;
+mod year;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use cli::{config::Config, selection::DaySelection};
+
+/// AOC solution scaffolding
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Workspace root containing the `years` directory
+    #[arg(short, long, global = true, default_value = ".")]
+    workspace_dir: PathBuf,
+    /// Data directory, or `data_dir` from aoc.toml, or `data` in the workspace
+    /// root if omitted
+    #[arg(short, long, global = true)]
+    data_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Add a day to a year crate: its solution module, tests, optional pest
+    /// grammar, registry entry, and empty example inputs
+    Day {
+        /// AOC challenge year, or `year` from aoc.toml, or the current AOC year
+        /// if omitted
+        #[arg(short, long, value_parser=clap::value_parser!(i32).range(2015..))]
+        year: Option<i32>,
+        /// Parse the input with a pest grammar under `grammars/`
+        #[arg(short, long)]
+        grammar: bool,
+        /// Problem day
+        #[arg(value_parser=clap::value_parser!(u16).range(1..=25))]
+        day: u16,
+    },
+    /// Add a year crate to the workspace with an empty registry and data
+    /// directory
+    Year {
+        /// Also scaffold a day, range of days (e.g. 1..=25), or all
+        #[arg(long)]
+        days: Option<DaySelection>,
+        /// AOC challenge year
+        #[arg(value_parser=clap::value_parser!(i32).range(2015..))]
+        year: i32,
+    },
+    /// Replace the `part_1.txt` and `part_2.txt` inputs of each day with a
+    /// single `input.txt`
+    Migrate {
+        /// AOC challenge year, or every year in the data directory if omitted
+        #[arg(short, long, value_parser=clap::value_parser!(i32).range(2015..))]
+        year: Option<i32>,
+    },
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let config = Config::global()?;
+    let data_dir = match args.data_dir {
+        Some(data_dir) => data_dir,
+        None => config.data_dir()?,
+    };
+    match args.command {
+        Command::Day { year, grammar, day } => {
+            let year = year.unwrap_or_else(|| config.year());
+            day::scaffold(&args.workspace_dir, &data_dir, year, day, grammar)
+        }
+        Command::Year { days, year } => year::scaffold(&args.workspace_dir, &data_dir, year, days),
+        Command::Migrate { year } => migrate::migrate(&data_dir, year),
+    }
+}
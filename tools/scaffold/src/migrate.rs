@@ -0,0 +1,142 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use cli::util::INPUT_FILE;
+
+/// Names of the per-part inputs that `input.txt` replaced.
+const PART_INPUTS: [&str; 2] = ["part_1.txt", "part_2.txt"];
+
+/// Converts the day directories of `year`, or of every year, from a copy of
+/// the real input per part to a single `input.txt`. Every day is attempted
+/// before failing on any that couldn't be converted.
+pub fn migrate(data_dir: &Path, year: Option<i32>) -> Result<()> {
+    let year_dirs = match year {
+        Some(year) => vec![data_dir.join(year.to_string())],
+        None => numbered_dirs(data_dir)?,
+    };
+    let (mut migrated, mut failed) = (0, 0);
+    for year_dir in year_dirs {
+        for day_dir in numbered_dirs(&year_dir)? {
+            match migrate_day(&day_dir) {
+                Ok(true) => {
+                    migrated += 1;
+                    println!("migrated {}", day_dir.display());
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    failed += 1;
+                    eprintln!("failed to migrate {}: {err:#}", day_dir.display());
+                }
+            }
+        }
+    }
+    println!("migrated {migrated} days");
+    if failed > 0 {
+        bail!("{failed} days couldn't be migrated");
+    }
+    Ok(())
+}
+
+/// Replaces `part_1.txt` and `part_2.txt` in `day_dir`, either of which may
+/// be a link to the other, with `input.txt`. Returns whether anything
+/// changed. Parts with different inputs, or an existing `input.txt` that
+/// differs from them, are an error and leave the directory untouched.
+pub fn migrate_day(day_dir: &Path) -> Result<bool> {
+    let legacy: Vec<PathBuf> = PART_INPUTS
+        .iter()
+        .map(|name| day_dir.join(name))
+        .filter(|path| path.symlink_metadata().is_ok())
+        .collect();
+    let Some(first) = legacy.first() else {
+        return Ok(false);
+    };
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+    };
+    let input = read(first)?;
+    for path in &legacy[1..] {
+        if read(path)? != input {
+            bail!(
+                "{} and {} differ, keep the day's input as {INPUT_FILE} by hand",
+                first.display(),
+                path.display()
+            );
+        }
+    }
+
+    let input_path = day_dir.join(INPUT_FILE);
+    match fs::read_to_string(&input_path) {
+        Ok(existing) if existing != input => {
+            bail!("{} differs from {}", input_path.display(), first.display())
+        }
+        Ok(_) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => fs::write(&input_path, &input)
+            .with_context(|| format!("failed to write {}", input_path.display()))?,
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", input_path.display()))
+        }
+    }
+    for path in legacy {
+        fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    Ok(true)
+}
+
+/// Subdirectories of `dir` named by a number, such as years and days, in
+/// order.
+fn numbered_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut dirs = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        let numbered = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.parse::<u32>().is_ok());
+        if numbered && path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_migrate_day() {
+        let dir = tempdir().unwrap();
+        let (linked, different) = (dir.path().join("01"), dir.path().join("02"));
+        fs::create_dir_all(&linked).unwrap();
+        fs::create_dir_all(&different).unwrap();
+        fs::write(linked.join("part_1.txt"), "1 2\n").unwrap();
+        std::os::unix::fs::symlink("part_1.txt", linked.join("part_2.txt")).unwrap();
+        fs::write(different.join("part_1.txt"), "1 2\n").unwrap();
+        fs::write(different.join("part_2.txt"), "3 4\n").unwrap();
+
+        let migrated = migrate_day(&linked).unwrap();
+        let input = fs::read_to_string(linked.join(INPUT_FILE)).unwrap();
+        let leftover = linked.join("part_2.txt").symlink_metadata().is_ok();
+        let rerun = migrate_day(&linked).unwrap();
+        let conflict = migrate_day(&different).is_err();
+        let untouched =
+            different.join("part_2.txt").exists() && !different.join(INPUT_FILE).exists();
+
+        assert!(migrated);
+        assert_eq!("1 2\n", input);
+        assert!(!leftover);
+        assert!(!rerun);
+        assert!(conflict);
+        assert!(untouched);
+    }
+}
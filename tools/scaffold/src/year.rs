@@ -0,0 +1,254 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use cli::selection::DaySelection;
+use regex::Regex;
+
+use crate::day;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/year/Cargo.toml.tmpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/year/lib.rs.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("../templates/year/main.rs.tmpl");
+const MAX_WIDTH: usize = 100;
+
+type Edit = fn(&str, i32) -> Result<String>;
+
+/// Generates the `years/aoc_<year>` crate with an empty registry, adds it to
+/// the workspace and the `aoc` runner, creates its data directory, and
+/// optionally scaffolds `days`.
+pub fn scaffold(
+    workspace_dir: &Path,
+    data_dir: &Path,
+    year: i32,
+    days: Option<DaySelection>,
+) -> Result<()> {
+    let crate_dir = day::year_dir(workspace_dir, year);
+    if crate_dir.exists() {
+        bail!("{} already exists", crate_dir.display());
+    }
+
+    let runner_dir = workspace_dir.join("tools").join("aoc");
+    let edits = [
+        (workspace_dir.join("Cargo.toml"), add_member as Edit),
+        (runner_dir.join("Cargo.toml"), add_runner_dependency),
+        (runner_dir.join("src").join("main.rs"), add_runner_registry),
+    ]
+    .into_iter()
+    .map(|(path, edit)| {
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let edited = edit(&source, year)
+            .with_context(|| format!("failed to add {year} to {}", path.display()))?;
+        Ok((path, edited))
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    let render = |template: &str| template.replace("{{year}}", &year.to_string());
+    fs::create_dir_all(crate_dir.join("src"))
+        .with_context(|| format!("failed to create {}", crate_dir.display()))?;
+    day::write_new(&crate_dir.join("Cargo.toml"), &render(CARGO_TEMPLATE))?;
+    day::write_new(&crate_dir.join("src").join("lib.rs"), &render(LIB_TEMPLATE))?;
+    day::write_new(
+        &crate_dir.join("src").join("main.rs"),
+        &render(MAIN_TEMPLATE),
+    )?;
+    for (path, edited) in edits {
+        fs::write(&path, edited).with_context(|| format!("failed to write {}", path.display()))?;
+        println!("added aoc_{year} to {}", path.display());
+    }
+
+    let year_data_dir = data_dir.join(year.to_string());
+    fs::create_dir_all(&year_data_dir)
+        .with_context(|| format!("failed to create {}", year_data_dir.display()))?;
+
+    for day in days.iter().flat_map(DaySelection::days) {
+        day::scaffold(workspace_dir, data_dir, year, day, false)?;
+    }
+    Ok(())
+}
+
+/// Adds the crate for `year` to the workspace `members` and
+/// `[workspace.dependencies]` of the workspace manifest, keeping both sorted.
+fn add_member(manifest: &str, year: i32) -> Result<String> {
+    let member = format!("years/aoc_{year}");
+
+    let members_pattern = Regex::new(r"(?s)members\s*=\s*\[(.*?)\]")?;
+    let captures = members_pattern
+        .captures(manifest)
+        .context("no workspace members")?;
+    let mut members: Vec<&str> = captures[1]
+        .split(',')
+        .map(|member| member.trim().trim_matches('"'))
+        .filter(|member| !member.is_empty())
+        .collect();
+    if members.contains(&member.as_str()) {
+        bail!("{member} is already a workspace member");
+    }
+    members.push(&member);
+    members.sort_unstable();
+    let members: String = members
+        .iter()
+        .map(|member| format!("    \"{member}\",\n"))
+        .collect::<Vec<_>>()
+        .concat();
+    let range = captures.get(0).context("no workspace members")?.range();
+    let manifest = format!(
+        "{}members = [\n{members}]{}",
+        &manifest[..range.start],
+        &manifest[range.end..]
+    );
+
+    let dependency = format!("aoc_{year} = {{ path = \"{member}\" }}\n");
+    insert_dependency(&manifest, "[workspace.dependencies]", year, &dependency)
+}
+
+fn add_runner_dependency(manifest: &str, year: i32) -> Result<String> {
+    let dependency = format!("aoc_{year} = {{ workspace = true }}\n");
+    insert_dependency(manifest, "[dependencies]", year, &dependency)
+}
+
+/// Inserts `dependency` into `section` of a manifest, ordered by year among
+/// the other year crates.
+fn insert_dependency(manifest: &str, section: &str, year: i32, dependency: &str) -> Result<String> {
+    let header = format!("{section}\n");
+    let start = manifest
+        .find(&header)
+        .with_context(|| format!("no {section}"))?
+        + header.len();
+    let end = manifest[start..]
+        .find("\n[")
+        .map_or(manifest.len(), |end| start + end + 1);
+    let year_pattern = Regex::new(r"(?m)^aoc_(\d{4}) = .*\n")?;
+    let mut insert_at = None;
+    for captures in year_pattern.captures_iter(&manifest[start..end]) {
+        let line = captures.get(0).context("no dependency line")?;
+        let existing: i32 = captures[1].parse()?;
+        if existing == year {
+            bail!("aoc_{year} is already a dependency");
+        }
+        if existing > year {
+            insert_at = Some(start + line.start());
+            break;
+        }
+        insert_at = Some(start + line.end());
+    }
+    let mut manifest = manifest.to_string();
+    manifest.insert_str(insert_at.unwrap_or(end), dependency);
+    Ok(manifest)
+}
+
+/// Adds the registry for `year` to the `cli::run_years` call of the `aoc`
+/// runner.
+fn add_runner_registry(source: &str, year: i32) -> Result<String> {
+    let registry = format!("aoc_{year}::registry()");
+    let pattern = Regex::new(r"(?s)run_years\(&\[(.*?)\]\)")?;
+    let captures = pattern.captures(source).context("no run_years call")?;
+    let mut registries: Vec<&str> = captures[1]
+        .split(',')
+        .map(str::trim)
+        .filter(|registry| !registry.is_empty())
+        .collect();
+    if registries.contains(&registry.as_str()) {
+        bail!("{registry} is already in the runner");
+    }
+    registries.push(&registry);
+    registries.sort_unstable();
+
+    let range = captures.get(0).context("no run_years call")?.range();
+    let line_start = source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &source[line_start..range.start];
+    let indent: String = prefix.chars().take_while(|c| c.is_whitespace()).collect();
+    let single_line = format!("run_years(&[{}])", registries.join(", "));
+    let call = if prefix.len() + single_line.len() <= MAX_WIDTH {
+        single_line
+    } else {
+        let registries: Vec<String> = registries
+            .iter()
+            .map(|registry| format!("{indent}    {registry},\n"))
+            .collect();
+        format!("run_years(&[\n{}{indent}])", registries.concat())
+    };
+    Ok(format!(
+        "{}{call}{}",
+        &source[..range.start],
+        &source[range.end..]
+    ))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[workspace]
+members = ["lib/cli", "years/aoc_2024"]
+
+[workspace.dependencies]
+cli = { path = "lib/cli" }
+aoc_2024 = { path = "years/aoc_2024" }
+anyhow = "1.0.94"
+"#;
+
+    #[test]
+    fn test_add_member() {
+        let manifest = add_member(MANIFEST, 2023).unwrap();
+        assert_eq!(
+            r#"[workspace]
+members = [
+    "lib/cli",
+    "years/aoc_2023",
+    "years/aoc_2024",
+]
+
+[workspace.dependencies]
+cli = { path = "lib/cli" }
+aoc_2023 = { path = "years/aoc_2023" }
+aoc_2024 = { path = "years/aoc_2024" }
+anyhow = "1.0.94"
+"#,
+            manifest
+        );
+        assert!(add_member(&manifest, 2023).is_err());
+        assert!(add_member(MANIFEST, 2024).is_err());
+        assert!(add_member(&manifest, 2025).unwrap().contains(
+            "aoc_2024 = { path = \"years/aoc_2024\" }\naoc_2025 = { path = \"years/aoc_2025\" }\n"
+        ));
+    }
+
+    #[test]
+    fn test_cargo_template() {
+        let manifest = CARGO_TEMPLATE.replace("{{year}}", "2025");
+        assert!(manifest.contains("name = \"aoc_2025\"\n"));
+        // Year binaries are built with `--features alloc-stats` like the runner.
+        assert!(manifest.contains("[features]\nalloc-stats = [\"cli/alloc-stats\"]\n"));
+    }
+
+    #[test]
+    fn test_add_runner_dependency() {
+        let manifest = "[package]\nname = \"aoc\"\n\n[dependencies]\ncli = { workspace = true }\n";
+        assert_eq!(
+            "[package]\nname = \"aoc\"\n\n[dependencies]\ncli = { workspace = true }\naoc_2015 = { workspace = true }\n",
+            add_runner_dependency(manifest, 2015).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_add_runner_registry() {
+        let source = "fn main() -> ExitCode {\n    cli::run_years(&[aoc_2024::registry()])\n}\n";
+        let source = add_runner_registry(source, 2023).unwrap();
+        assert_eq!(
+            "fn main() -> ExitCode {\n    cli::run_years(&[aoc_2023::registry(), aoc_2024::registry()])\n}\n",
+            source
+        );
+        assert!(add_runner_registry(&source, 2024).is_err());
+
+        let source = add_runner_registry(&source, 2015).unwrap();
+        let source = add_runner_registry(&source, 2016).unwrap();
+        assert_eq!(
+            "fn main() -> ExitCode {\n    cli::run_years(&[\n        aoc_2015::registry(),\n        \
+             aoc_2016::registry(),\n        aoc_2023::registry(),\n        aoc_2024::registry(),\n    \
+             ])\n}\n",
+            source
+        );
+    }
+}
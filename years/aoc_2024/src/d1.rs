@@ -1,44 +1,55 @@
-use std::path::Path;
+//! Day 1: Historian Hysteria
 
 use anyhow::{bail, Context, Result};
-use cli::util::file_path;
 use rug::{Complete, Integer};
 
-use cli::part::Part;
-use util::{counting::count_distinct, io::read_with_callback};
+use cli::{answer::Answer, solution::Solution};
+use util::{
+    counting::count_distinct,
+    io::{for_each_line, InputSource},
+};
 
-use crate::example_dir_for_day;
+/// Day 1 solution. The parsed input is the left and right location lists.
+pub struct Day;
 
-pub(crate) fn run(base_dir: &Path, part: Part, example: bool) -> Result<String> {
-    let path = file_path(&example_dir_for_day(base_dir, 1), part, example);
-    let (lhs, rhs) = read(&path)?;
-    let solution = match part {
-        Part::P1 => solve_p1(lhs, rhs),
-        Part::P2 => solve_p2(&lhs, &rhs),
-    };
-    Ok(solution.to_string())
-}
+impl Solution for Day {
+    const DAY: u16 = 1;
+
+    type Input = (Vec<Integer>, Vec<Integer>);
 
-fn read(path: &Path) -> Result<(Vec<Integer>, Vec<Integer>)> {
-    let mut lhs: Vec<Integer> = vec![];
-    let mut rhs: Vec<Integer> = vec![];
+    fn parse(input: &InputSource) -> Result<Self::Input> {
+        let mut lhs: Vec<Integer> = vec![];
+        let mut rhs: Vec<Integer> = vec![];
 
-    read_with_callback(path, &mut |line| {
-        let mut split_iter = line.split("   ");
-        match (split_iter.next(), split_iter.next()) {
-            (Some(l), Some(r)) => {
-                lhs.push(Integer::parse(l).context("not an integer")?.complete());
-                rhs.push(Integer::parse(r).context("not an integer")?.complete());
-                Ok(())
+        for_each_line(input.reader()?, &mut |line| {
+            let mut split_iter = line.split("   ");
+            match (split_iter.next(), split_iter.next()) {
+                (Some(l), Some(r)) => {
+                    lhs.push(Integer::parse(l).context("not an integer")?.complete());
+                    rhs.push(Integer::parse(r).context("not an integer")?.complete());
+                    Ok(())
+                }
+                (_, _) => bail!("unexpected file format"),
             }
-            (_, _) => bail!("unexpected file format"),
-        }
-    })?;
+        })?;
+
+        Ok((lhs, rhs))
+    }
+
+    fn part1((lhs, rhs): &Self::Input) -> Result<Answer> {
+        Ok(solve_p1(lhs, rhs).into())
+    }
 
-    Ok((lhs, rhs))
+    fn part2((lhs, rhs): &Self::Input) -> Result<Answer> {
+        Ok(solve_p2(lhs, rhs).into())
+    }
 }
 
-fn solve_p1(mut lhs: Vec<Integer>, mut rhs: Vec<Integer>) -> Integer {
+/// Total distance between the sorted left and right lists.
+#[must_use]
+pub fn solve_p1(lhs: &[Integer], rhs: &[Integer]) -> Integer {
+    let mut lhs = lhs.to_vec();
+    let mut rhs = rhs.to_vec();
     lhs.sort();
     rhs.sort();
     lhs.iter()
@@ -47,7 +58,10 @@ fn solve_p1(mut lhs: Vec<Integer>, mut rhs: Vec<Integer>) -> Integer {
         .sum()
 }
 
-fn solve_p2(lhs: &Vec<Integer>, rhs: &[Integer]) -> Integer {
+/// Similarity score: each left value times its number of occurrences in the
+/// right list.
+#[must_use]
+pub fn solve_p2(lhs: &[Integer], rhs: &[Integer]) -> Integer {
     let rhs_counts = count_distinct(rhs.iter());
     let mut total = Integer::new();
     for entry in lhs {
@@ -59,44 +73,14 @@ fn solve_p2(lhs: &Vec<Integer>, rhs: &[Integer]) -> Integer {
 }
 
 #[cfg(test)]
-#[allow(clippy::unwrap_used)]
 mod tests {
-    use super::*;
-    use cli::util::default_data_dir;
-
-    #[test]
-    fn test_example_1() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 1), Part::P1, true);
-        let (lhs, rhs) = read(&path).unwrap();
-        assert_eq!(Integer::from(11), solve_p1(lhs, rhs));
-    }
-
-    #[test]
-    fn test_example_2() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 1), Part::P2, true);
-        let (lhs, rhs) = read(&path).unwrap();
-        assert_eq!(Integer::from(31), solve_p2(&lhs, &rhs));
-    }
-
-    #[test]
-    fn test_problem_1() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 1),
-            Part::P1,
-            false,
-        );
-        let (lhs, rhs) = read(&path).unwrap();
-        assert_eq!(Integer::from(1_506_483), solve_p1(lhs, rhs));
-    }
+    use super::Day;
 
-    #[test]
-    fn test_problem_2() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 1),
-            Part::P2,
-            false,
-        );
-        let (lhs, rhs) = read(&path).unwrap();
-        assert_eq!(Integer::from(23_126_924), solve_p2(&lhs, &rhs));
+    cli::day_tests! {
+        Day;
+        example_1 = 11,
+        example_2 = 31,
+        part_1 = 1_506_483,
+        part_2 = 23_126_924,
     }
 }
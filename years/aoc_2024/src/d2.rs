@@ -1,20 +1,42 @@
-use std::path::Path;
+//! Day 2: Red-Nosed Reports
 
 use anyhow::Result;
 
-use cli::{part::Part, util::file_path};
-use util::data::{all_negative_i64, all_positive_i64, nth_difference_i64, table::Table};
+use cli::{
+    answer::Answer,
+    part::Part,
+    solution::{Solution, Variant},
+};
+use util::{
+    data::{all_negative_i64, all_positive_i64, nth_difference_i64, table::Table},
+    io::InputSource,
+};
 
-use crate::example_dir_for_day;
+/// Day 2 solution. The parsed input is one report of levels per row.
+pub struct Day;
 
-pub(crate) fn run(base_dir: &Path, part: Part, example: bool) -> Result<String> {
-    let path = file_path(&example_dir_for_day(base_dir, 2), part, example);
-    let table = Table::<i64>::read_from_path(&path)?;
-    let solution = match part {
-        Part::P1 => solve_p1(&table),
-        Part::P2 => solve_p2(&table),
-    };
-    Ok(solution.to_string())
+impl Solution for Day {
+    const DAY: u16 = 2;
+
+    type Input = Table<i64>;
+
+    fn parse(input: &InputSource) -> Result<Self::Input> {
+        Table::<i64>::read_from_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![Variant::new("linear", Part::P2, |input| {
+            Ok(solve_p2_linear(input).into())
+        })]
+    }
 }
 
 fn is_safe(row: &[i64]) -> bool {
@@ -28,7 +50,9 @@ fn is_safe(row: &[i64]) -> bool {
     }
 }
 
-fn solve_p1(table: &Table<i64>) -> usize {
+/// Number of safe reports.
+#[must_use]
+pub fn solve_p1(table: &Table<i64>) -> usize {
     table
         .iter()
         .map(|x| is_safe(x.as_slice()))
@@ -36,7 +60,9 @@ fn solve_p1(table: &Table<i64>) -> usize {
         .sum()
 }
 
-fn solve_p2(table: &Table<i64>) -> usize {
+/// Number of reports that are safe after removing at most one level.
+#[must_use]
+pub fn solve_p2(table: &Table<i64>) -> usize {
     fn try_solve(row: &[i64]) -> bool {
         if is_safe(row) {
             return true;
@@ -57,45 +83,53 @@ fn solve_p2(table: &Table<i64>) -> usize {
         .sum()
 }
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::*;
-    use cli::util::default_data_dir;
-
-    #[test]
-    fn test_example_1() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 2), Part::P1, true);
-        let table = Table::<i64>::read_from_path(&path).expect("valid table");
-        assert_eq!(2, solve_p1(&table));
-    }
+/// Whether the levels of `row`, without the level at `skip`, all step in the
+/// direction of `sign` by one to three.
+fn is_safe_without(row: &[i64], skip: usize, sign: i64) -> bool {
+    let levels = row
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != skip)
+        .map(|(_, level)| level);
+    levels
+        .clone()
+        .zip(levels.skip(1))
+        .all(|(lhs, rhs)| (1..=3).contains(&((rhs - lhs) * sign)))
+}
 
-    #[test]
-    fn test_example_2() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 2), Part::P2, true);
-        let table = Table::<i64>::read_from_path(&path).expect("valid table");
-        assert_eq!(4, solve_p2(&table));
+/// Number of reports that are safe after removing at most one level, checking
+/// each report in linear time. Any removal that makes a report safe must
+/// remove one of the two levels of its first unsafe step, so at most two
+/// removals need to be tried for each direction.
+#[must_use]
+pub fn solve_p2_linear(table: &Table<i64>) -> usize {
+    fn try_solve(row: &[i64]) -> bool {
+        [1, -1].into_iter().any(|sign| {
+            let unsafe_step = row
+                .windows(2)
+                .position(|pair| !(1..=3).contains(&((pair[1] - pair[0]) * sign)));
+            match unsafe_step {
+                None => true,
+                Some(idx) => is_safe_without(row, idx, sign) || is_safe_without(row, idx + 1, sign),
+            }
+        })
     }
+    table
+        .iter()
+        .map(|x| try_solve(x.as_slice()))
+        .map(usize::from)
+        .sum()
+}
 
-    #[test]
-    fn test_part_1() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 2),
-            Part::P1,
-            false,
-        );
-        let table = Table::<i64>::read_from_path(&path).expect("valid table");
-        assert_eq!(326, solve_p1(&table));
-    }
+#[cfg(test)]
+mod tests {
+    use super::Day;
 
-    #[test]
-    fn test_part_2() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 2),
-            Part::P2,
-            false,
-        );
-        let table = Table::<i64>::read_from_path(&path).expect("valid table");
-        assert_eq!(381, solve_p2(&table));
+    cli::day_tests! {
+        Day;
+        example_1 = 2,
+        example_2 = 4,
+        part_1 = 326,
+        part_2 = 381,
     }
 }
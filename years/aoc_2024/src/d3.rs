@@ -1,30 +1,41 @@
-use std::{fs, path::Path};
+//! Day 3: Mull It Over
 
 use anyhow::{Context, Result};
-use cli::{part::Part, util::file_path};
+use cli::{answer::Answer, solution::Solution};
 use pest::Parser;
 use pest_derive::Parser;
-
-use crate::example_dir_for_day;
+use util::io::InputSource;
 
 #[derive(Parser)]
 #[grammar = "grammars/d3.pest"]
 struct D3Parser;
 
-enum Token {
+/// An instruction found in the corrupted memory
+pub enum Token {
     Mul { lhs: i64, rhs: i64 },
     Do,
     Dont,
 }
 
-pub(crate) fn run(base_dir: &Path, part: Part, example: bool) -> Result<String> {
-    let path = file_path(&example_dir_for_day(base_dir, 3), part, example);
-    let tokens = parse_file(&path)?;
-    let solution = match part {
-        Part::P1 => solve_p1(&tokens),
-        Part::P2 => solve_p2(&tokens),
-    };
-    Ok(solution.to_string())
+/// Day 3 solution. The parsed input is the instructions in corrupted memory.
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u16 = 3;
+
+    type Input = Vec<Token>;
+
+    fn parse(input: &InputSource) -> Result<Self::Input> {
+        parse_input(&input.read_to_string()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
 }
 
 fn eval(tokens: &[Token], ignore_control_flow: bool) -> i64 {
@@ -77,58 +88,27 @@ fn parse_input(input: &str) -> Result<Vec<Token>> {
     Ok(values)
 }
 
-fn parse_file(path: &Path) -> Result<Vec<Token>> {
-    let input: String = fs::read_to_string(path)?;
-    parse_input(&input)
-}
-
-fn solve_p1(tokens: &[Token]) -> i64 {
+/// Sum of every multiplication, ignoring `do()` and `don't()`.
+#[must_use]
+pub fn solve_p1(tokens: &[Token]) -> i64 {
     eval(tokens, true)
 }
 
-fn solve_p2(tokens: &[Token]) -> i64 {
+/// Sum of the multiplications enabled by the most recent `do()` or `don't()`.
+#[must_use]
+pub fn solve_p2(tokens: &[Token]) -> i64 {
     eval(tokens, false)
 }
 
 #[cfg(test)]
-#[allow(clippy::unwrap_used)]
 mod tests {
-    use super::*;
-    use cli::util::default_data_dir;
-
-    #[test]
-    fn test_example_1() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 3), Part::P1, true);
-        let tokens = parse_file(&path).unwrap();
-        assert_eq!(161, solve_p1(&tokens));
-    }
-
-    #[test]
-    fn test_example_2() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 3), Part::P2, true);
-        let tokens = parse_file(&path).unwrap();
-        assert_eq!(48, solve_p2(&tokens));
-    }
-
-    #[test]
-    fn test_part_1() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 3),
-            Part::P1,
-            false,
-        );
-        let tokens = parse_file(&path).unwrap();
-        assert_eq!(189_600_467, solve_p1(&tokens));
-    }
+    use super::Day;
 
-    #[test]
-    fn test_part_2() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 3),
-            Part::P2,
-            false,
-        );
-        let tokens = parse_file(&path).unwrap();
-        assert_eq!(107_069_718, solve_p2(&tokens));
+    cli::day_tests! {
+        Day;
+        example_1 = 161,
+        example_2 = 48,
+        part_1 = 189_600_467,
+        part_2 = 107_069_718,
     }
 }
@@ -1,14 +1,13 @@
-use std::path::Path;
+//! Day 4: Ceres Search
 
 use anyhow::Result;
 
-use cli::{part::Part, util::file_path};
-use util::data::{
-    table::Table, Coordinate2d, Direction2d, IterationInstruction, TwoDimensionalArray,
+use cli::{answer::Answer, solution::Solution};
+use util::{
+    data::{table::Table, Coordinate2d, Direction2d, IterationInstruction, TwoDimensionalArray},
+    io::InputSource,
 };
 
-use crate::example_dir_for_day;
-
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum State {
     #[default]
@@ -39,17 +38,30 @@ impl State {
     }
 }
 
-pub(crate) fn run(base_dir: &Path, part: Part, example: bool) -> Result<String> {
-    let path = file_path(&example_dir_for_day(base_dir, 4), part, example);
-    let table = Table::<char>::read_from_path(&path)?;
-    let solution = match part {
-        Part::P1 => solve_p1(&table),
-        Part::P2 => solve_p2(&table),
-    };
-    Ok(solution.to_string())
+/// Day 4 solution. The parsed input is the word search grid.
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u16 = 4;
+
+    type Input = Table<char>;
+
+    fn parse(input: &InputSource) -> Result<Self::Input> {
+        Table::<char>::read_from_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(solve_p2(input).into())
+    }
 }
 
-fn solve_p1(table: &Table<char>) -> usize {
+/// Number of times `XMAS` appears in any direction.
+#[must_use]
+pub fn solve_p1(table: &Table<char>) -> usize {
     let mut prev_direction: Option<Direction2d> = None;
     let mut state = State::default();
     let mut count = 0;
@@ -77,7 +89,9 @@ fn solve_p1(table: &Table<char>) -> usize {
     count
 }
 
-fn solve_p2(table: &Table<char>) -> usize {
+/// Number of `MAS` crosses centered on an `A`.
+#[must_use]
+pub fn solve_p2(table: &Table<char>) -> usize {
     fn table_value(table: &Table<char>, coordinate: Option<Coordinate2d>) -> Option<&char> {
         let coordinate = coordinate?;
         table.get(coordinate.1, coordinate.0)
@@ -111,46 +125,15 @@ fn solve_p2(table: &Table<char>) -> usize {
     count
 }
 
-#[allow(clippy::unwrap_used)]
 #[cfg(test)]
 mod tests {
-    use cli::util::default_data_dir;
-
-    use super::*;
-
-    #[test]
-    fn test_example_1() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 4), Part::P1, true);
-        let table = Table::<char>::read_from_path(&path).unwrap();
-        assert_eq!(18, solve_p1(&table));
-    }
-
-    #[test]
-    fn test_example_2() {
-        let path = file_path(&example_dir_for_day(&default_data_dir(), 4), Part::P2, true);
-        let table = Table::<char>::read_from_path(&path).unwrap();
-        assert_eq!(9, solve_p2(&table));
-    }
-
-    #[test]
-    fn test_part_1() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 4),
-            Part::P1,
-            false,
-        );
-        let table = Table::<char>::read_from_path(&path).unwrap();
-        assert_eq!(2662, solve_p1(&table));
-    }
-
-    #[test]
-    fn test_part_2() {
-        let path = file_path(
-            &example_dir_for_day(&default_data_dir(), 4),
-            Part::P2,
-            false,
-        );
-        let table = Table::<char>::read_from_path(&path).unwrap();
-        assert_eq!(2034, solve_p2(&table));
+    use super::Day;
+
+    cli::day_tests! {
+        Day;
+        example_1 = 18,
+        example_2 = 9,
+        part_1 = 2662,
+        part_2 = 2034,
     }
 }
@@ -0,0 +1,26 @@
+#![deny(clippy::all, clippy::pedantic, clippy::panic, clippy::unwrap_used)]
+#![allow(clippy::missing_errors_doc, clippy::module_name_repetitions)]
+
+//! Advent of Code 2024 solutions.
+//!
+//! Each day module `dN` exposes a `Day` type implementing
+//! [`Solution`](cli::solution::Solution), whose `parse` reads the puzzle input
+//! from a file, stdin, or a string and whose `part1` and `part2` return the
+//! answers, along with the underlying `solve_p1` and `solve_p2` functions that
+//! operate on the parsed input. [`registry`] collects every day for the
+//! runner.
+//!
+//! ```
+//! use aoc_2024::d1::Day;
+//! use cli::solution::Solution;
+//! use util::io::InputSource;
+//!
+//! let input = Day::parse(&InputSource::text("3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"))?;
+//! assert_eq!("11", Day::part1(&input)?.to_string());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+/// The AOC year these solutions are for
+pub const YEAR: i32 = 2024;
+
+cli::days!(YEAR; d1, d2, d3, d4);